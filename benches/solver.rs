@@ -0,0 +1,87 @@
+//! Benchmarks of the hot paths of the solvers on the sample board,
+//! run with `cargo bench`. The BFS on a single thread and on every core
+//! is compared with `--features parallel`.
+use blocked::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+
+/// Generating the moves of a board, which checks the occupied tiles
+/// for every step of every piece
+fn moves(c: &mut Criterion) {
+    let board = Board::sample();
+    c.bench_function("all_moves", |b| b.iter(|| black_box(&board).all_moves()));
+    c.bench_function("future_boards", |b| {
+        b.iter(|| black_box(&board).future_boards())
+    });
+}
+
+/// Playing a move, which only updates the tiles of the moved piece,
+/// and playing and undoing it on the same board
+fn play(c: &mut Criterion) {
+    let board = Board::sample();
+    let mov = Move::Up(5, 1);
+    c.bench_function("play", |b| b.iter(|| black_box(&board).play(&mov)));
+    c.bench_function("play_in_place", |b| {
+        let mut board = board.clone();
+        b.iter(|| {
+            board.play_in_place(&mov);
+            board.undo_in_place(&mov);
+        })
+    });
+}
+
+/// Solving the board with the default table, with the table hashed
+/// with SipHash, from a cache of moves and as a bitboard
+fn solve_sample(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    group.sample_size(10);
+
+    let board = Board::sample();
+    group.bench_function("board_hasher", |b| {
+        b.iter(|| solve(board.clone(), &mut Visited::default()))
+    });
+    group.bench_function("sip_hasher", |b| {
+        b.iter(|| {
+            let mut visited: HashMap<Board, Option<Move>, RandomState> = HashMap::default();
+            solve(board.clone(), &mut visited)
+        })
+    });
+
+    // the cache is filled by the first solve and only read by the others
+    let mut cache = MoveCache::new();
+    solve_with_cache(board.clone(), &mut cache);
+    group.bench_function("move_cache", |b| {
+        b.iter(|| solve_with_cache(board.clone(), &mut cache))
+    });
+
+    let bitboard = board.to_bitboard().expect("the sample board fits in a bitboard");
+    group.bench_function("bitboard", |b| b.iter(|| solve_bitboard(bitboard.clone())));
+    group.finish();
+}
+
+/// Solving a board with large waves of boards, expanding each wave
+/// on a single thread and on every core
+#[cfg(feature = "parallel")]
+fn parallel_solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel");
+    group.sample_size(10);
+
+    // the sample with room to spare, so that far more boards are reachable
+    let grid = "...abbb\n...ac..\nXX.ec.f\n.ggehhf\n.ijkk.f\n.ijll..\n.......";
+    let board = Board::from_ascii(grid, (6, 2)).expect("the board is valid");
+    let single = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    group.bench_function("sequential", |b| {
+        b.iter(|| single.install(|| solve(board.clone(), &mut Visited::default())))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| solve(board.clone(), &mut Visited::default()))
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+criterion_group!(benches, moves, play, solve_sample);
+#[cfg(feature = "parallel")]
+criterion_group!(benches, moves, play, solve_sample, parallel_solve);
+criterion_main!(benches);
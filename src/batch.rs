@@ -0,0 +1,85 @@
+use crate::board::*;
+use crate::solver::{solve_with_limits, SolveError};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How long `analyze_batch` searches a single board before giving up
+const BOARD_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many boards `analyze_batch` visits for a single board before giving up
+const BOARD_MAX_STATES: usize = 5_000_000;
+
+/// A summary of solving a batch of boards, see `analyze_batch`
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct BatchReport {
+    /// The number of boards that have a solution
+    pub solvable: usize,
+    /// The number of boards that have no solution
+    pub unsolvable: usize,
+    /// The indexes of the boards that the solver gave up on, as they took
+    /// too long or too much memory. They count as neither solvable nor not.
+    pub gave_up: Vec<usize>,
+    /// The number of solvable boards with an optimal solution of each length
+    pub lengths: BTreeMap<u32, usize>,
+    /// The index of the solvable board with the longest optimal solution,
+    /// the first one if there are several, and the length of that solution
+    pub hardest: Option<(usize, u32)>,
+}
+
+/// Solve every board and summarize the results. Each board gets a limited
+/// time and number of states to be solved in, so that a single hard board
+/// can't stall the batch, and the boards that run out are listed apart.
+pub fn analyze_batch(boards: Vec<Board>) -> BatchReport {
+    let mut report = BatchReport::default();
+    for (index, board) in boards.into_iter().enumerate() {
+        match solve_with_limits(board, BOARD_TIMEOUT, BOARD_MAX_STATES) {
+            Ok((_, steps)) => {
+                report.solvable += 1;
+                *report.lengths.entry(steps).or_insert(0) += 1;
+                if report.hardest.is_none_or(|(_, most)| steps > most) {
+                    report.hardest = Some((index, steps));
+                }
+            }
+            Err(SolveError::Unsolvable) => report.unsolvable += 1,
+            Err(SolveError::Timeout { .. }) | Err(SolveError::StateLimitExceeded { .. }) => {
+                report.gave_up.push(index)
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_of_a_mixed_batch() {
+        let blocked = Board::new(
+            4,
+            1,
+            (3, 0),
+            vec![
+                Piece::marked((0, 0), 2, Direction::Horizontal),
+                Piece::frozen((2, 0), 1, Direction::Horizontal),
+            ],
+        );
+        let won = Board::new(
+            4,
+            1,
+            (3, 0),
+            vec![Piece::marked((2, 0), 2, Direction::Horizontal)],
+        );
+        let short = won.play(&Move::Left(0, 2));
+        let boards = vec![short, Board::sample(), blocked, won, Board::sample()];
+
+        let report = analyze_batch(boards);
+        assert_eq!((report.solvable, report.unsolvable), (4, 1));
+        assert!(report.gave_up.is_empty());
+        let lengths: Vec<(u32, usize)> = report.lengths.into_iter().collect();
+        assert_eq!(lengths, [(0, 1), (1, 1), (50, 2)]);
+        // the first of the two hardest boards
+        assert_eq!(report.hardest, Some((1, 50)));
+
+        assert_eq!(analyze_batch(vec![]), BatchReport::default());
+    }
+}
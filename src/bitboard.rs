@@ -0,0 +1,248 @@
+use crate::board::*;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+/// The largest width and height of a board that fits in a `BitBoard`
+const MAX_SIZE: u32 = 8;
+
+/// The bit of a tile, every row takes up 8 bits
+/// no matter how wide the board is
+fn bit((x, y): Tile) -> u64 {
+    if x < MAX_SIZE && y < MAX_SIZE {
+        1 << (y * MAX_SIZE + x)
+    } else {
+        0
+    }
+}
+
+/// The bits of all the given tiles
+fn bits(tiles: impl IntoIterator<Item = Tile>) -> u64 {
+    tiles.into_iter().fold(0, |bits, t| bits | bit(t))
+}
+
+/// The parts of a board that don't change when its pieces move
+#[derive(Debug)]
+struct Layout {
+    /// The board the bitboard was made from
+    board: Board,
+    /// The tiles along the edges of the board, which pieces can't move past
+    first_column: u64,
+    last_column: u64,
+    first_row: u64,
+    last_row: u64,
+    /// The bit of the goal of every piece that is marked
+    goals: Vec<Option<u64>>,
+}
+
+/// A board of at most 8x8 tiles where the occupied tiles and every piece
+/// are packed into the bits of a `u64`, so that moves are found and played
+/// with bit operations. The pieces keep the ids they have on the `Board`.
+#[derive(Debug, Clone)]
+pub struct BitBoard {
+    layout: Arc<Layout>,
+    pieces: Vec<u64>,
+    occupied: u64,
+}
+
+/// Only the pieces are compared and hashed, bitboards
+/// are assumed to come from the same board
+impl PartialEq for BitBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.pieces == other.pieces
+    }
+}
+
+impl Eq for BitBoard {}
+
+impl Hash for BitBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pieces.hash(state);
+    }
+}
+
+impl Board {
+    /// Pack the board into a `BitBoard`, or `None` if it is larger
+    /// than 8x8 tiles or its topology isn't rectangular
+    pub fn to_bitboard(&self) -> Option<BitBoard> {
+        if self.width > MAX_SIZE || self.height > MAX_SIZE || !self.topology().is_rectangular() {
+            return None;
+        }
+
+        let pieces: Vec<u64> = self
+            .pieces()
+            .iter()
+            .map(|p| bits(p.occupies()))
+            .collect();
+        let walls = bits(self.walls.iter().copied());
+
+        let layout = Layout {
+            first_column: bits((0..self.height).map(|y| (0, y))),
+            last_column: bits((0..self.height).map(|y| (self.width.saturating_sub(1), y))),
+            first_row: bits((0..self.width).map(|x| (x, 0))),
+            last_row: bits((0..self.width).map(|x| (x, self.height.saturating_sub(1)))),
+            goals: self
+                .pieces()
+                .iter()
+                .map(|p| {
+                    if p.is_marked() {
+                        Some(bit(p.goal().unwrap_or(self.goal)))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            board: self.clone(),
+        };
+
+        Some(BitBoard {
+            occupied: pieces.iter().fold(walls, |bits, p| bits | p),
+            layout: Arc::new(layout),
+            pieces,
+        })
+    }
+}
+
+impl BitBoard {
+    /// Unpack the bitboard into a `Board`
+    pub fn to_board(&self) -> Board {
+        let locations: Vec<Tile> = self
+            .pieces
+            .iter()
+            .map(|p| {
+                let i = p.trailing_zeros();
+                (i % MAX_SIZE, i / MAX_SIZE)
+            })
+            .collect();
+        self.layout.board.with_locations(&locations)
+    }
+
+    /// Check if every marked piece covers its goal, with the tile
+    /// given by the win mode of the board
+    pub fn is_won(&self) -> bool {
+        let win_mode = self.layout.board.win_mode();
+        let mut marked = self
+            .layout
+            .goals
+            .iter()
+            .zip(&self.pieces)
+            .filter_map(|(goal, &p)| goal.map(|goal| (goal, p)))
+            .peekable();
+        // the location of a piece is its lowest bit and the far end its highest
+        marked.peek().is_some()
+            && marked.all(|(goal, p)| match win_mode {
+                WinMode::AnyCell => goal & p != 0,
+                WinMode::Anchor => goal == p & p.wrapping_neg(),
+                WinMode::FarEnd => goal == 1 << (63 - p.leading_zeros()),
+            })
+    }
+
+    /// Find all the possible moves, in the same order as `Board::all_moves`
+    pub fn all_moves(&self) -> Vec<Move> {
+        let layout = &self.layout;
+        let mut moves = vec![];
+        for (id, piece) in layout.board.pieces().iter().enumerate() {
+            if piece.moves_along(Direction::Horizontal) {
+                self.slides(id, layout.last_column, |m| m << 1, Move::Right, &mut moves);
+                self.slides(id, layout.first_column, |m| m >> 1, Move::Left, &mut moves);
+            }
+            if piece.moves_along(Direction::Vertical) {
+                self.slides(id, layout.first_row, |m| m >> MAX_SIZE, Move::Up, &mut moves);
+                self.slides(id, layout.last_row, |m| m << MAX_SIZE, Move::Down, &mut moves);
+            }
+        }
+        moves
+    }
+
+    /// Add the moves of a piece in one direction, one step at a time until
+    /// it reaches the edge of the board or the next tile is taken
+    fn slides(
+        &self,
+        id: usize,
+        edge: u64,
+        shift: fn(u64) -> u64,
+        kind: fn(usize, u32) -> Move,
+        moves: &mut Vec<Move>,
+    ) {
+        let mut current = self.pieces[id];
+        let mut steps = 1;
+        while current & edge == 0 {
+            let next = shift(current);
+            if next & !current & self.occupied != 0 {
+                break;
+            }
+            moves.push(kind(id, steps));
+            current = next;
+            steps += 1;
+        }
+    }
+
+    /// Given a move, generate a new bitboard.
+    /// The move is assumed to be legal like in `Board::play`.
+    pub fn play(&self, mov: &Move) -> BitBoard {
+        let mut pieces = self.pieces.clone();
+        let mask = &mut pieces[mov.get_piece()];
+        let old = *mask;
+        *mask = match *mov {
+            Move::Left(_, steps) => old >> steps,
+            Move::Right(_, steps) => old << steps,
+            Move::Up(_, steps) => old >> (steps * MAX_SIZE),
+            Move::Down(_, steps) => old << (steps * MAX_SIZE),
+        };
+
+        BitBoard {
+            occupied: self.occupied ^ old ^ *mask,
+            layout: Arc::clone(&self.layout),
+            pieces,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_boards() {
+        let board = Board::sample();
+        assert_eq!(board.to_bitboard().unwrap().to_board(), board);
+        let played = board.play(&Move::Up(5, 1)).play(&Move::Right(11, 2));
+        assert_eq!(played.to_bitboard().unwrap().to_board(), played);
+
+        let pieces = vec![
+            Piece::marked((0, 7), 2, Direction::Horizontal),
+            Piece::new((7, 0), 8, Direction::Vertical),
+        ];
+        let largest = Board::try_with_walls(8, 8, (6, 7), pieces, vec![(3, 3)]).unwrap();
+        assert_eq!(largest.to_bitboard().unwrap().to_board(), largest);
+    }
+
+    #[test]
+    fn moves_match_the_board() {
+        let mut board = Board::sample();
+        let mut bitboard = board.to_bitboard().unwrap();
+        for mov in [Move::Up(5, 1), Move::Right(11, 2), Move::Right(10, 1)] {
+            let mut expected = board.all_moves();
+            expected.sort();
+            let mut moves = bitboard.all_moves();
+            moves.sort();
+            assert_eq!(moves, expected);
+            assert_eq!(bitboard.is_won(), board.is_won);
+
+            board.play_in_place(&mov);
+            bitboard = bitboard.play(&mov);
+            assert_eq!(bitboard.to_board(), board);
+        }
+    }
+
+    #[test]
+    fn boards_larger_than_8x8_are_rejected() {
+        let marked = || vec![Piece::marked((0, 0), 2, Direction::Horizontal)];
+        assert!(Board::new(9, 1, (8, 0), marked()).to_bitboard().is_none());
+        assert!(Board::new(2, 9, (1, 0), marked()).to_bitboard().is_none());
+        assert!(Board::new(8, 8, (7, 0), marked()).to_bitboard().is_some());
+        let ring = Board::new(8, 1, (7, 0), marked()).with_topology(&crate::ToroidalTopology);
+        assert!(ring.to_bitboard().is_none());
+    }
+}
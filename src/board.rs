@@ -1,221 +1,2633 @@
-use std::fmt;
-
-pub type Tile = (u32, u32);
-
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-pub enum Direction {
-    Horizontal,
-    Vertical,
-}
-
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-pub struct Piece {
-    size: u32,
-    location: Tile,
-    direction: Direction,
-    marked: bool,
-}
-
-impl Piece {
-    /// Create a new piece
-    pub fn new(location: Tile, size: u32, direction: Direction) -> Self {
-        Piece {
-            marked: false,
-            location,
-            direction,
-            size,
-        }
-    }
-    /// Create a new marked piece
-    pub fn marked(location: Tile, size: u32, direction: Direction) -> Self {
-        Piece {
-            marked: true,
-            location,
-            direction,
-            size,
-        }
-    }
-
-    pub fn occupies(&self) -> Vec<Tile> {
-        let (x, y) = self.location;
-        (0..self.size)
-            .into_iter()
-            .map(|i| match self.direction {
-                Direction::Horizontal => (x + i, y),
-                Direction::Vertical => (x, y + i),
-            })
-            .collect()
-    }
-}
-
-#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
-pub enum Move {
-    Left(Tile, u32),
-    Right(Tile, u32),
-    Up(Tile, u32),
-    Down(Tile, u32),
-}
-
-impl Move {
-    /// Get the tile from which the move
-    /// occurs
-    pub fn get_tile(&self) -> Tile {
-        match *self {
-            Move::Left(t, _) => t,
-            Move::Right(t, _) => t,
-            Move::Up(t, _) => t,
-            Move::Down(t, _) => t,
-        }
-    }
-}
-
-impl fmt::Display for Move {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (tile, dir, steps) = match self {
-            Move::Left(t, steps)    => (t, "left", steps),
-            Move::Right(t, steps)   => (t, "right", steps),
-            Move::Up(t, steps)      => (t, "up", steps),
-            Move::Down(t, steps)    => (t, "down", steps),
-        };
-
-        write!(f, "Move ({},{}) {} by {} steps", tile.0, tile.1, dir, steps)
-    }
-}
-
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-pub struct Board {
-    pub width: u32,
-    pub height: u32,
-    pub goal: Tile,
-    pub is_won: bool,
-    pieces: Vec<Piece>,
-    pub occupied_tiles: Vec<Tile>,
-}
-
-impl Board {
-    /// Create a new board
-    pub fn new(width: u32, height: u32, goal: Tile, pieces: Vec<Piece>) -> Self {
-        let occupied_tiles = Board::occupied_tiles(&pieces);
-        let is_won = pieces
-            .iter()
-            .find(|p| p.marked && p.occupies().contains(&goal))
-            .is_some();
-
-        Board {
-            occupied_tiles,
-            is_won,
-            width,
-            height,
-            pieces,
-            goal,
-        }
-    }
-
-    /// Find all the possible moves
-    pub fn all_moves(&self) -> Vec<Move> {
-        let mut moves = vec![];
-        for piece in &self.pieces {
-            let (x, y) = piece.location;
-
-            match piece.direction {
-                Direction::Horizontal => {
-                    let (start, end) = (x, x + piece.size - 1);
-
-                    for i in 1.. {
-                        if !self.empty_tile((end + i, y)) {
-                            break;
-                        }
-                        moves.push(Move::Right((x, y), i));
-                    }
-
-                    for i in 1.. {
-                        if start < i || !self.empty_tile((start - i, y)) {
-                            break;
-                        }
-                        moves.push(Move::Left((x, y), i));
-                    }
-                }
-                Direction::Vertical => {
-                    let (start, end) = (y, y + piece.size - 1);
-
-                    for i in 1.. {
-                        if start < i || !self.empty_tile((x, start - i)) {
-                            break;
-                        }
-                        moves.push(Move::Up((x, y), i));
-                    }
-
-                    for i in 1.. {
-                        if !self.empty_tile((x, end + i)) {
-                            break;
-                        }
-                        moves.push(Move::Down((x, y), i));
-                    }
-                }
-            }
-        }
-        moves
-    }
-
-    /// Get tuples of future boards and the move to get there
-    pub fn future_boards(&self) -> Vec<(Board, Move)> {
-        self.all_moves()
-            .into_iter()
-            .map(|next| (self.play(&next), next))
-            .collect()
-    }
-
-    /// Given a move, generate a new board
-    pub fn play(&self, mov: &Move) -> Board {
-        let pieces = self
-            .pieces
-            .clone()
-            .into_iter()
-            .map(|mut p| {
-                if p.location == mov.get_tile() {
-                    // if the current piece is the one that the move
-                    // concerns, then move it:
-                    let (x, y) = p.location;
-                    match mov {
-                        Move::Left(_, steps) => p.location = (x - steps, y),
-                        Move::Right(_, steps) => p.location = (x + steps, y),
-                        Move::Up(_, steps) => p.location = (x, y - steps),
-                        Move::Down(_, steps) => p.location = (x, y + steps),
-                    }
-                }
-                p
-            })
-            .collect();
-
-        Board::new(self.width, self.height, self.goal, pieces)
-    }
-
-    /// Given a move, reverse the action and return that board.
-    pub fn undo(&self, mov: &Move) -> Board {
-        let reverse_move = match *mov {
-            Move::Left((x, y), steps) => Move::Right((x - steps, y), steps),
-            Move::Right((x, y), steps) => Move::Left((x + steps, y), steps),
-            Move::Up((x, y), steps) => Move::Down((x, y - steps), steps),
-            Move::Down((x, y), steps) => Move::Up((x, y + steps), steps),
-        };
-
-        self.play(&reverse_move)
-    }
-
-    /// Given a list of pieces, find all the occupied tiles
-    /// This functions is used when initing new boards.
-    pub fn occupied_tiles(pieces: &Vec<Piece>) -> Vec<Tile> {
-        pieces.iter().flat_map(|p| p.occupies()).collect()
-    }
-
-    /// Check if a tile is free
-    pub fn empty_tile(&self, t: Tile) -> bool {
-        self.tile_exists(t) && !self.occupied_tiles.contains(&t)
-    }
-
-    /// Check if a tile exists on the board,
-    /// i.e has a lower value than the width/height and, greater or equal to 0
-    pub fn tile_exists(&self, (x, y): Tile) -> bool {
-        x < self.width && y < self.height
-    }
-}
+use crate::topology::{BoardTopology, Topology};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use core::convert::TryFrom;
+
+pub type Tile = (u32, u32);
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// A side of the board
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Where the marked pieces have to go to win a board
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Goal {
+    /// A tile that a marked piece has to cover. A tile just past the right
+    /// or the bottom edge is a gap in the wall like the exit of Rush Hour,
+    /// which is reached by covering the tile next to it like an `Edge`.
+    Tile(Tile),
+    /// An exit on a side of the board in the given row or column, like
+    /// the exit of Rush Hour. A piece can't move off the board, so the
+    /// exit is reached by covering the tile next to it.
+    Edge(Side, u32),
+}
+
+impl Goal {
+    /// Get the tile that has to be covered to reach the goal
+    /// on a board of the given size
+    pub fn tile(self, width: u32, height: u32) -> Tile {
+        match self {
+            // an empty board has no edge to leave by
+            Goal::Tile((x, y)) if x == width && y < height && x > 0 => (x - 1, y),
+            Goal::Tile((x, y)) if y == height && x < width && y > 0 => (x, y - 1),
+            Goal::Tile(tile) => tile,
+            Goal::Edge(Side::Left, y) => (0, y),
+            Goal::Edge(Side::Right, y) => (width.saturating_sub(1), y),
+            Goal::Edge(Side::Top, x) => (x, 0),
+            Goal::Edge(Side::Bottom, x) => (x, height.saturating_sub(1)),
+        }
+    }
+}
+
+/// Which tile of a marked piece has to cover its goal to win a board
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WinMode {
+    /// Any tile of the piece
+    #[default]
+    AnyCell,
+    /// The location of the piece, its left or top tile
+    Anchor,
+    /// The other end of the piece, its right or bottom tile
+    FarEnd,
+}
+
+impl WinMode {
+    /// Get the mode for the piece turned around, the ends swap places
+    fn flipped(self) -> WinMode {
+        match self {
+            WinMode::AnyCell => WinMode::AnyCell,
+            WinMode::Anchor => WinMode::FarEnd,
+            WinMode::FarEnd => WinMode::Anchor,
+        }
+    }
+}
+
+impl From<Tile> for Goal {
+    fn from(tile: Tile) -> Self {
+        Goal::Tile(tile)
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Piece {
+    // assigned by the board, so there is no need to store it
+    #[cfg_attr(feature = "serde", serde(skip))]
+    id: usize,
+    size: u32,
+    location: Tile,
+    direction: Direction,
+    marked: bool,
+    /// The goal of a marked piece, if it has a goal of its own
+    /// rather than the goal of the board
+    #[cfg_attr(feature = "serde", serde(default))]
+    goal: Option<Tile>,
+    /// A frozen piece takes up its tiles but never moves
+    #[cfg_attr(feature = "serde", serde(default))]
+    frozen: bool,
+}
+
+impl Piece {
+    /// Create a new piece
+    pub fn new(location: Tile, size: u32, direction: Direction) -> Self {
+        Piece {
+            id: 0,
+            marked: false,
+            goal: None,
+            frozen: false,
+            location,
+            direction,
+            size,
+        }
+    }
+    /// Create a new marked piece
+    pub fn marked(location: Tile, size: u32, direction: Direction) -> Self {
+        Piece {
+            id: 0,
+            marked: true,
+            goal: None,
+            frozen: false,
+            location,
+            direction,
+            size,
+        }
+    }
+    /// Create a new marked piece that has to reach its own goal
+    /// instead of the goal of the board
+    pub fn marked_with_goal(location: Tile, size: u32, direction: Direction, goal: Tile) -> Self {
+        Piece {
+            id: 0,
+            marked: true,
+            goal: Some(goal),
+            frozen: false,
+            location,
+            direction,
+            size,
+        }
+    }
+    /// Create a new frozen piece, that blocks the other pieces like a wall
+    /// but covers several tiles
+    pub fn frozen(location: Tile, size: u32, direction: Direction) -> Self {
+        Piece {
+            id: 0,
+            marked: false,
+            goal: None,
+            frozen: true,
+            location,
+            direction,
+            size,
+        }
+    }
+
+    /// Get the piece frozen or not, whatever else it is
+    pub(crate) fn with_frozen(mut self, frozen: bool) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    /// Create a piece from the tiles that a letter covers in
+    /// an ASCII grid. The tiles are expected in row-major order.
+    fn from_run(letter: char, tiles: &[Tile]) -> Result<Self, ParseError> {
+        let (x, y) = tiles[0];
+        let size = tiles.len() as u32;
+
+        let horizontal = tiles
+            .iter()
+            .zip(0..)
+            .all(|(&tile, i)| tile == (x + i, y));
+        let vertical = tiles
+            .iter()
+            .zip(0..)
+            .all(|(&tile, i)| tile == (x, y + i));
+
+        let direction = match (horizontal, vertical) {
+            (true, _) => Direction::Horizontal,
+            (false, true) => Direction::Vertical,
+            (false, false) => return Err(ParseError::BrokenPiece(letter)),
+        };
+
+        if letter == 'X' {
+            Ok(Piece::marked((x, y), size, direction))
+        } else {
+            Ok(Piece::new((x, y), size, direction))
+        }
+    }
+
+    /// Get the id of the piece, its index on the board
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Get the top left tile of the piece
+    pub fn location(&self) -> Tile {
+        self.location
+    }
+
+    /// Get the number of tiles the piece covers
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Get the direction the piece moves in
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Check if the piece can move along the given axis. A piece of a single
+    /// tile has no length to tell its axis, so it moves along both, and a
+    /// frozen piece moves along neither.
+    pub fn moves_along(&self, direction: Direction) -> bool {
+        !self.frozen && (self.size == 1 || self.direction == direction)
+    }
+
+    /// Check if this is a frozen piece
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Check if this is a marked piece
+    pub fn is_marked(&self) -> bool {
+        self.marked
+    }
+
+    /// Get the goal of a marked piece, if it has its own
+    pub fn goal(&self) -> Option<Tile> {
+        self.goal
+    }
+
+    /// Get the tiles of the piece on a rectangular board,
+    /// see `Board::piece_tiles` for other topologies
+    pub fn occupies(&self) -> Vec<Tile> {
+        self.tiles().collect()
+    }
+
+    /// Check if mirroring the board left to right and/or top to bottom
+    /// flips the axis of the piece, so that its ends swap places
+    fn turned_around(&self, flip_x: bool, flip_y: bool) -> bool {
+        let flipped = match self.direction {
+            Direction::Horizontal => flip_x,
+            Direction::Vertical => flip_y,
+        };
+        // both ends of a piece of a single tile are the same tile
+        flipped && self.size > 1
+    }
+
+    /// Get the side of the board that the rest of the piece is towards,
+    /// seen from its location
+    fn towards_end(&self) -> Side {
+        match self.direction {
+            Direction::Horizontal => Side::Right,
+            Direction::Vertical => Side::Bottom,
+        }
+    }
+
+    /// Iterate over the tiles of the piece without collecting them
+    fn tiles(&self) -> impl Iterator<Item = Tile> {
+        let (x, y) = self.location;
+        let direction = self.direction;
+        (0..self.size).map(move |i| match direction {
+            Direction::Horizontal => (x + i, y),
+            Direction::Vertical => (x, y + i),
+        })
+    }
+}
+
+/// A move of the piece with the given id by a number of steps.
+/// Moves are ordered by direction first, left, right, up and then down,
+/// then by the id of the piece and last by the number of steps.
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Move {
+    Left(usize, u32),
+    Right(usize, u32),
+    Up(usize, u32),
+    Down(usize, u32),
+}
+
+impl Move {
+    /// Get the id of the piece that is moved
+    pub fn get_piece(&self) -> usize {
+        match *self {
+            Move::Left(id, _) => id,
+            Move::Right(id, _) => id,
+            Move::Up(id, _) => id,
+            Move::Down(id, _) => id,
+        }
+    }
+
+    /// The cost of the move when every tile that a piece travels costs
+    /// as much as the size of the piece, see `dijkstra_solve`.
+    /// Panics if the piece isn't on the board, see `try_cost`.
+    pub fn cost(&self, board: &Board) -> u32 {
+        board.pieces[self.get_piece()].size * self.steps()
+    }
+
+    /// Get the cost of the move like `cost`, or an error if
+    /// the piece isn't on the board
+    pub fn try_cost(&self, board: &Board) -> Result<u32, MoveError> {
+        let id = self.get_piece();
+        let piece = board.pieces.get(id).ok_or(MoveError::UnknownPiece(id))?;
+        Ok(piece.size.saturating_mul(self.steps()))
+    }
+
+    /// Get the number of steps of the move
+    pub fn steps(&self) -> u32 {
+        match *self {
+            Move::Left(_, steps) => steps,
+            Move::Right(_, steps) => steps,
+            Move::Up(_, steps) => steps,
+            Move::Down(_, steps) => steps,
+        }
+    }
+
+    /// Get the same move with another number of steps
+    pub fn with_steps(&self, steps: u32) -> Move {
+        match *self {
+            Move::Left(id, _) => Move::Left(id, steps),
+            Move::Right(id, _) => Move::Right(id, steps),
+            Move::Up(id, _) => Move::Up(id, steps),
+            Move::Down(id, _) => Move::Down(id, steps),
+        }
+    }
+
+    /// Get the move that takes the piece back to where it came from
+    pub fn reverse(&self) -> Move {
+        match *self {
+            Move::Left(id, steps) => Move::Right(id, steps),
+            Move::Right(id, steps) => Move::Left(id, steps),
+            Move::Up(id, steps) => Move::Down(id, steps),
+            Move::Down(id, steps) => Move::Up(id, steps),
+        }
+    }
+
+    /// Get the side of the board the piece moves towards
+    pub fn side(&self) -> Side {
+        match self {
+            Move::Left(..) => Side::Left,
+            Move::Right(..) => Side::Right,
+            Move::Up(..) => Side::Top,
+            Move::Down(..) => Side::Bottom,
+        }
+    }
+
+    /// Get the axis the piece moves along
+    pub fn axis(&self) -> Direction {
+        match self {
+            Move::Left(..) | Move::Right(..) => Direction::Horizontal,
+            Move::Up(..) | Move::Down(..) => Direction::Vertical,
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (id, dir, steps) = match self {
+            Move::Left(id, steps)    => (id, "left", steps),
+            Move::Right(id, steps)   => (id, "right", steps),
+            Move::Up(id, steps)      => (id, "up", steps),
+            Move::Down(id, steps)    => (id, "down", steps),
+        };
+
+        write!(f, "Move piece {} {} by {} steps", id, dir, steps)
+    }
+}
+
+impl fmt::Display for Board {
+    /// Draw the board row by row. Pieces are lettered `a`, `b`, `c`...
+    /// by id, which is their row-major order on a board that was just
+    /// created or parsed, the marked piece is drawn as `X`,
+    /// walls as `#`, empty tiles as `.` and the goals as `+` if nothing
+    /// covers them.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut grid = vec!['.'; (self.width * self.height) as usize];
+        let index = |(x, y): Tile| (y * self.width + x) as usize;
+
+        let goals = self.pieces.iter().filter_map(|p| p.goal);
+        for goal in goals.chain(Some(self.goal)) {
+            if self.tile_exists(goal) {
+                grid[index(goal)] = '+';
+            }
+        }
+
+        for &wall in &self.walls {
+            grid[index(wall)] = '#';
+        }
+
+        for (i, piece) in self.pieces.iter().enumerate() {
+            let letter = if piece.marked { 'X' } else { piece_letter(i) };
+            for tile in self.piece_tiles(piece) {
+                grid[index(tile)] = letter;
+            }
+        }
+
+        for (y, row) in grid.chunks(self.width as usize).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+/// The letter used to draw the piece with the given index
+pub(crate) fn piece_letter(index: usize) -> char {
+    if index < 26 {
+        (b'a' + index as u8) as char
+    } else {
+        '?'
+    }
+}
+
+/// Errors that can occur when parsing a board from text
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum ParseError {
+    /// The grid contains no rows
+    Empty,
+    /// A row does not have the same length as the first row
+    RaggedRow { row: u32, expected: u32, found: u32 },
+    /// A character that is neither `.`, `#` nor a letter
+    UnexpectedChar(char, Tile),
+    /// The cells of a piece do not form a straight, contiguous run
+    BrokenPiece(char),
+    /// A move that isn't written as a piece, a direction and a distance
+    InvalidNotation(String),
+    /// A move of a piece that isn't on the board
+    UnknownPiece(String),
+    /// A puzzle string that doesn't have one character per tile
+    WrongLength { expected: u32, found: u32 },
+    /// A puzzle string without the marked piece
+    NoMarkedPiece,
+    /// The goal is not a tile of the grid
+    GoalOutOfBounds(Tile),
+    /// A goal line that isn't written like `goal: 5,2`
+    InvalidGoal(String),
+    /// The puzzle with the given index among several couldn't be parsed
+    InPuzzle { index: usize, error: Box<ParseError> },
+    /// The bytes end early, go on after the board or have a value
+    /// that no board is encoded with, at the given offset
+    InvalidBytes(usize),
+    /// The pieces were read but don't make a valid board
+    InvalidBoard(BoardError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "the grid is empty"),
+            ParseError::RaggedRow { row, expected, found } => write!(
+                f,
+                "row {} has {} cells but {} were expected",
+                row, found, expected
+            ),
+            ParseError::UnexpectedChar(c, (x, y)) => {
+                write!(f, "unexpected character '{}' at ({},{})", c, x, y)
+            }
+            ParseError::BrokenPiece(c) => write!(
+                f,
+                "piece '{}' is not a straight horizontal or vertical run",
+                c
+            ),
+            ParseError::InvalidNotation(s) => write!(f, "'{}' is not a valid move", s),
+            ParseError::UnknownPiece(s) => write!(f, "there is no piece {} on the board", s),
+            ParseError::WrongLength { expected, found } => write!(
+                f,
+                "the puzzle has {} cells but {} were expected",
+                found, expected
+            ),
+            ParseError::NoMarkedPiece => write!(f, "the puzzle has no marked piece"),
+            ParseError::GoalOutOfBounds((x, y)) => {
+                write!(f, "the goal ({},{}) is not on the board", x, y)
+            }
+            ParseError::InvalidGoal(s) => write!(f, "'{}' is not a goal like 'goal: 5,2'", s),
+            ParseError::InPuzzle { index, error } => write!(f, "puzzle {}: {}", index, error),
+            ParseError::InvalidBytes(offset) => {
+                write!(f, "the bytes don't encode a board, at byte {}", offset)
+            }
+            ParseError::InvalidBoard(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Errors that can occur when creating a board, each variant
+/// names the index of the offending piece
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum BoardError {
+    /// The piece has a size of zero
+    EmptyPiece(usize),
+    /// The piece extends off the board
+    OutOfBounds(usize),
+    /// The piece covers a tile that a wall or an earlier piece already covers
+    Overlap(usize),
+    /// No goal was given to the builder
+    MissingGoal,
+    /// The goal of the board or of a marked piece is not on the board
+    GoalOutOfBounds,
+    /// The wall with the given index is not on the board
+    WallOutOfBounds(usize),
+    /// The board has the given number of marked pieces, but it needs
+    /// exactly one unless the marked pieces have goals of their own
+    MarkedPieceCount(usize),
+    /// The board has more tiles than fit in a `u32`, or than
+    /// `Board::from_bytes` decodes
+    TooLarge,
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::EmptyPiece(i) => write!(f, "piece {} has a size of zero", i),
+            BoardError::OutOfBounds(i) => write!(f, "piece {} extends off the board", i),
+            BoardError::Overlap(i) => {
+                write!(f, "piece {} overlaps another piece or a wall", i)
+            }
+            BoardError::MissingGoal => write!(f, "the board has no goal"),
+            BoardError::GoalOutOfBounds => write!(f, "the goal is not on the board"),
+            BoardError::WallOutOfBounds(i) => write!(f, "wall {} is not on the board", i),
+            BoardError::MarkedPieceCount(n) => write!(
+                f,
+                "the board has {} marked pieces but needs one, \
+                 or several with goals of their own",
+                n
+            ),
+            BoardError::TooLarge => write!(f, "the board has too many tiles"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BoardError {}
+
+/// Reasons that a move can't be played on a board
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum MoveError {
+    /// There is no piece with the id of the move
+    UnknownPiece(usize),
+    /// The piece can't move in the direction of the move
+    WrongDirection,
+    /// The move has zero steps
+    NoSteps,
+    /// A piece, a wall or the edge of the board is in the way
+    Blocked,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::UnknownPiece(id) => write!(f, "there is no piece {}", id),
+            MoveError::WrongDirection => write!(f, "the piece can't move in that direction"),
+            MoveError::NoSteps => write!(f, "the move has zero steps"),
+            MoveError::Blocked => write!(f, "the path of the piece is blocked"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MoveError {}
+
+/// A move in a list of moves that couldn't be played
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ReplayError {
+    /// The index of the move in the list
+    pub index: usize,
+    pub mov: Move,
+    pub reason: MoveError,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} ({}) is illegal: {}", self.index, self.mov, self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReplayError {}
+
+/// A board reached by a move, together with the piece that moved
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Transition {
+    /// The board after the move
+    pub board: Board,
+    pub mov: Move,
+    /// The id of the piece that moved
+    pub piece: usize,
+}
+
+/// A set of tiles on a board, stored as one bit per tile
+/// so that lookups don't have to scan anything
+#[derive(Hash, Eq, PartialEq, Debug, Clone)]
+pub struct TileSet {
+    width: u32,
+    height: u32,
+    bits: Vec<u64>,
+}
+
+impl TileSet {
+    /// Create an empty set for a board of the given size
+    pub fn new(width: u32, height: u32) -> Self {
+        let tiles = (width * height) as usize;
+        TileSet {
+            width,
+            height,
+            bits: vec![0; tiles.div_ceil(64)],
+        }
+    }
+
+    fn index(&self, (x, y): Tile) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some((y * self.width + x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Add a tile to the set, tiles outside of the board are ignored
+    pub fn insert(&mut self, t: Tile) {
+        if let Some(i) = self.index(t) {
+            self.bits[i / 64] |= 1 << (i % 64);
+        }
+    }
+
+    /// Remove a tile from the set
+    pub fn remove(&mut self, t: Tile) {
+        if let Some(i) = self.index(t) {
+            self.bits[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    /// Check if a tile is in the set
+    pub fn contains(&self, t: &Tile) -> bool {
+        match self.index(*t) {
+            Some(i) => self.bits[i / 64] & (1 << (i % 64)) != 0,
+            None => false,
+        }
+    }
+
+    /// Get all the tiles in the set, in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = Tile> + '_ {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |i| (i % width, i / width))
+            .filter(move |t| self.contains(t))
+    }
+
+    /// Get the number of tiles in the set
+    pub fn len(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Check if the set has no tiles
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&b| b == 0)
+    }
+}
+
+/// A board with its pieces. Boards from the constructors and parsers are
+/// checked to be valid, so none of the methods panic on them unless said so.
+/// The public fields can be changed, but a board that doesn't pass
+/// `check_invariants` afterwards may make the methods panic.
+#[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "BoardData"))]
+pub struct Board {
+    pub width: u32,
+    pub height: u32,
+    pub goal: Tile,
+    // the win flag and the occupied tiles follow from the pieces,
+    // they are recomputed when a board is deserialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub is_won: bool,
+    pieces: Vec<Piece>,
+    /// Tiles that are permanently blocked
+    pub walls: Vec<Tile>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub occupied_tiles: TileSet,
+    /// The Zobrist hash of the piece locations, see `zobrist_key`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    zobrist: u64,
+    win_mode: WinMode,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    topology: BoardTopology,
+}
+
+/// Equal boards have their pieces in the same locations and so the same
+/// Zobrist hash, which is all that is hashed. Boards that only differ in
+/// their size, goal or walls collide, but those are rarely in the same table.
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
+}
+
+/// The key that sorts tiles top to bottom and then left to right
+fn row_major((x, y): Tile) -> (u32, u32) {
+    (y, x)
+}
+
+/// Get the tiles that a piece covers on a board of the given size and
+/// topology, see `Board::piece_tiles`
+fn tiles_on(
+    topology: BoardTopology,
+    width: u32,
+    height: u32,
+    piece: &Piece,
+) -> impl Iterator<Item = Tile> {
+    let location = piece.location;
+    let towards = piece.towards_end();
+    (0..piece.size).map_while(move |i| topology.0.step(location, towards, i, width, height))
+}
+
+/// The pseudo random value that the piece with the given id at the given
+/// location contributes to the Zobrist hash of a board. It is computed
+/// with SplitMix64 rather than looked up, so boards of any size work.
+fn zobrist_key(id: usize, (x, y): Tile) -> u64 {
+    split_mix((id as u64) << 40 | (x as u64) << 20 | y as u64)
+}
+
+/// A step of SplitMix64, which scrambles the bits of a number
+fn split_mix(z: u64) -> u64 {
+    let mut z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The serialized fields of a board
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct BoardData {
+    width: u32,
+    height: u32,
+    goal: Tile,
+    pieces: Vec<Piece>,
+    #[serde(default)]
+    walls: Vec<Tile>,
+    #[serde(default)]
+    win_mode: WinMode,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BoardData> for Board {
+    type Error = BoardError;
+
+    fn try_from(data: BoardData) -> Result<Self, Self::Error> {
+        let win_mode = data.win_mode;
+        Board::try_with_walls(data.width, data.height, data.goal, data.pieces, data.walls)
+            .map(|board| board.with_win_mode(win_mode))
+    }
+}
+
+impl Board {
+    /// Create a new board. The pieces are sorted by their location, top to
+    /// bottom and then left to right, and get their id from their position
+    /// in that order, so the order of the vec doesn't matter. Playing moves
+    /// doesn't reorder them. The goal is a tile or a `Goal`.
+    /// Panics if the board is invalid, see `try_new`.
+    pub fn new(width: u32, height: u32, goal: impl Into<Goal>, pieces: Vec<Piece>) -> Self {
+        match Board::try_new(width, height, goal, pieces) {
+            Ok(board) => board,
+            Err(e) => panic!("invalid board: {}", e),
+        }
+    }
+
+    /// Create a new board, checking that no piece has a size of zero,
+    /// extends off the board or overlaps another piece, and that the goals
+    /// are on the board. There has to be one marked piece, or several if all
+    /// but one have their own goals.
+    pub fn try_new(
+        width: u32,
+        height: u32,
+        goal: impl Into<Goal>,
+        pieces: Vec<Piece>,
+    ) -> Result<Self, BoardError> {
+        Board::try_with_walls(width, height, goal, pieces, vec![])
+    }
+
+    /// Create a new board with walls, tiles that no piece can move onto
+    /// or through. The pieces are checked like in `try_new`, every wall
+    /// has to be on the board and the number of tiles has to fit in a `u32`.
+    pub fn try_with_walls(
+        width: u32,
+        height: u32,
+        goal: impl Into<Goal>,
+        pieces: Vec<Piece>,
+        walls: Vec<Tile>,
+    ) -> Result<Self, BoardError> {
+        if width.checked_mul(height).is_none() {
+            return Err(BoardError::TooLarge);
+        }
+        let goal = goal.into().tile(width, height);
+        // an exit in a row or column that doesn't exist is off the board too
+        if goal.0 >= width || goal.1 >= height {
+            return Err(BoardError::GoalOutOfBounds);
+        }
+
+        let mut occupied = TileSet::new(width, height);
+        for (i, &(x, y)) in walls.iter().enumerate() {
+            if x >= width || y >= height {
+                return Err(BoardError::WallOutOfBounds(i));
+            }
+            occupied.insert((x, y));
+        }
+
+        for (i, piece) in pieces.iter().enumerate() {
+            if piece.size == 0 {
+                return Err(BoardError::EmptyPiece(i));
+            }
+            // checked before listing the tiles, which could go past `u32::MAX`
+            let (along, length) = match piece.direction {
+                Direction::Horizontal => (piece.location.0, width),
+                Direction::Vertical => (piece.location.1, height),
+            };
+            if piece.size > length || along > length - piece.size {
+                return Err(BoardError::OutOfBounds(i));
+            }
+
+            for (x, y) in piece.occupies() {
+                if x >= width || y >= height {
+                    return Err(BoardError::OutOfBounds(i));
+                }
+                if occupied.contains(&(x, y)) {
+                    return Err(BoardError::Overlap(i));
+                }
+                occupied.insert((x, y));
+            }
+        }
+
+        // only one marked piece can go to the goal of the board
+        let marked = pieces.iter().filter(|p| p.marked).count();
+        let to_board_goal = pieces.iter().filter(|p| p.marked && p.goal.is_none()).count();
+        if marked == 0 || to_board_goal > 1 {
+            return Err(BoardError::MarkedPieceCount(marked));
+        }
+
+        // the goals of the pieces can be gaps in the wall too
+        let (mut pieces, mut walls) = (pieces, walls);
+        for piece in &mut pieces {
+            if let Some(goal) = piece.goal {
+                let (x, y) = Goal::Tile(goal).tile(width, height);
+                if x >= width || y >= height {
+                    return Err(BoardError::GoalOutOfBounds);
+                }
+                piece.goal = Some((x, y));
+            }
+        }
+        // the same board built from pieces in another order should be equal
+        pieces.sort_by_key(|p| row_major(p.location));
+        walls.sort_by_key(|&wall| row_major(wall));
+        let topology = BoardTopology::default();
+        Ok(Board::from_pieces(width, height, goal, pieces, walls, WinMode::default(), topology))
+    }
+
+    /// Create a board without validating the pieces,
+    /// used when the pieces are known to be valid
+    fn from_pieces(
+        width: u32,
+        height: u32,
+        goal: Tile,
+        pieces: Vec<Piece>,
+        walls: Vec<Tile>,
+        win_mode: WinMode,
+        topology: BoardTopology,
+    ) -> Self {
+        let pieces: Vec<Piece> = pieces
+            .into_iter()
+            .enumerate()
+            .map(|(id, mut p)| {
+                p.id = id;
+                p
+            })
+            .collect();
+        let zobrist = pieces
+            .iter()
+            .fold(0, |hash, p| hash ^ zobrist_key(p.id, p.location));
+
+        let mut board = Board {
+            occupied_tiles: TileSet::new(width, height),
+            is_won: false,
+            zobrist,
+            width,
+            height,
+            pieces,
+            walls,
+            goal,
+            win_mode,
+            topology,
+        };
+        board.update_tiles();
+        board
+    }
+
+    /// Recompute the occupied tiles and the win flag
+    /// after the pieces or the topology changed
+    fn update_tiles(&mut self) {
+        let mut occupied = TileSet::new(self.width, self.height);
+        self.pieces
+            .iter()
+            .flat_map(|p| self.piece_tiles(p))
+            .chain(self.walls.iter().copied())
+            .for_each(|t| occupied.insert(t));
+        self.occupied_tiles = occupied;
+        self.is_won = self.won(&self.pieces);
+    }
+
+    /// Check if every marked piece among the pieces has reached its goal
+    fn won(&self, pieces: &[Piece]) -> bool {
+        let mut marked = pieces.iter().filter(|p| p.marked).peekable();
+        marked.peek().is_some() && marked.all(|p| self.reached_goal(p))
+    }
+
+    /// Check if a marked piece covers its own goal, or the goal of the
+    /// board if it doesn't have one, with the tile of the win mode
+    pub(crate) fn reached_goal(&self, piece: &Piece) -> bool {
+        let goal = piece.goal.unwrap_or(self.goal);
+        match self.win_mode {
+            WinMode::AnyCell => self.piece_tiles(piece).any(|t| t == goal),
+            WinMode::Anchor => piece.location == goal,
+            WinMode::FarEnd => self.piece_tiles(piece).last() == Some(goal),
+        }
+    }
+
+    /// Get the tiles that a piece covers on this board, in order from its
+    /// location, which wrap around the edges with `ToroidalTopology`
+    pub fn piece_tiles(&self, piece: &Piece) -> impl Iterator<Item = Tile> + '_ {
+        tiles_on(self.topology, self.width, self.height, piece)
+    }
+
+    /// Get the first and the last tile of a piece on this board
+    pub(crate) fn piece_ends(&self, piece: &Piece) -> (Tile, Tile) {
+        let last = piece.size.saturating_sub(1);
+        let end = self.step(piece.location, piece.towards_end(), last);
+        (piece.location, end.unwrap_or(piece.location))
+    }
+
+    /// Get the tile `steps` tiles away towards a side, see `Topology::step`
+    pub(crate) fn step(&self, tile: Tile, towards: Side, steps: u32) -> Option<Tile> {
+        self.topology.0.step(tile, towards, steps, self.width, self.height)
+    }
+
+    /// Get how the tiles of the board are connected
+    pub fn topology(&self) -> &'static dyn Topology {
+        self.topology.0
+    }
+
+    /// Get the board with another topology, boards are created with
+    /// `RectTopology`. The pieces keep their locations. The topology is
+    /// not serialized, so it has to be set again on deserialized boards.
+    pub fn with_topology(mut self, topology: &'static dyn Topology) -> Board {
+        self.topology = BoardTopology(topology);
+        self.update_tiles();
+        self
+    }
+
+    /// Get which tile of the marked pieces has to cover the goal
+    pub fn win_mode(&self) -> WinMode {
+        self.win_mode
+    }
+
+    /// Get the board with another win mode, boards are created
+    /// with `WinMode::AnyCell`
+    pub fn with_win_mode(mut self, win_mode: WinMode) -> Board {
+        self.win_mode = win_mode;
+        self.is_won = self.won(&self.pieces);
+        self
+    }
+
+    /// Parse a board from an ASCII grid, one line per row.
+    /// `.` is an empty tile, `#` is a wall, every letter is a piece and
+    /// `X` is the marked piece, which every grid needs. The goal can't be
+    /// inferred from the grid so it has to be given separately, as a tile
+    /// or a `Goal`.
+    pub fn from_ascii(grid: &str, goal: impl Into<Goal>) -> Result<Self, ParseError> {
+        let rows: Vec<&str> = grid
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if rows.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let width = rows[0].chars().count() as u32;
+        let height = rows.len() as u32;
+
+        // the tiles of every letter, in order of first appearance
+        let mut runs: Vec<(char, Vec<Tile>)> = vec![];
+        let mut walls = vec![];
+        for (y, row) in rows.iter().enumerate() {
+            let found = row.chars().count() as u32;
+            if found != width {
+                return Err(ParseError::RaggedRow {
+                    row: y as u32,
+                    expected: width,
+                    found,
+                });
+            }
+
+            for (x, c) in row.chars().enumerate() {
+                let tile = (x as u32, y as u32);
+                match c {
+                    '.' => {}
+                    '#' => walls.push(tile),
+                    c if c.is_ascii_alphabetic() => {
+                        match runs.iter_mut().find(|(letter, _)| *letter == c) {
+                            Some((_, tiles)) => tiles.push(tile),
+                            None => runs.push((c, vec![tile])),
+                        }
+                    }
+                    c => return Err(ParseError::UnexpectedChar(c, tile)),
+                }
+            }
+        }
+
+        let pieces = runs
+            .into_iter()
+            .map(|(c, tiles)| Piece::from_run(c, &tiles))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let goal = goal.into().tile(width, height);
+        if goal.0 >= width || goal.1 >= height {
+            return Err(ParseError::GoalOutOfBounds(goal));
+        }
+        Board::try_with_walls(width, height, goal, pieces, walls).map_err(|e| match e {
+            BoardError::MarkedPieceCount(0) => ParseError::NoMarkedPiece,
+            e => ParseError::InvalidBoard(e),
+        })
+    }
+
+    /// Parse a 6x6 puzzle in the format of the common Rush Hour puzzle
+    /// databases, 36 characters in row-major order where `o` is an empty
+    /// tile, `x` is the marked piece and every other letter is a piece.
+    /// The goal is the exit on the right edge of the row of the marked piece.
+    pub fn from_rush_hour_string(puzzle: &str) -> Result<Self, ParseError> {
+        const SIZE: u32 = 6;
+
+        let cells: Vec<char> = puzzle.trim().chars().collect();
+        if cells.len() as u32 != SIZE * SIZE {
+            return Err(ParseError::WrongLength {
+                expected: SIZE * SIZE,
+                found: cells.len() as u32,
+            });
+        }
+
+        let row = match cells.iter().position(|&c| c == 'x') {
+            Some(i) => i as u32 / SIZE,
+            None => return Err(ParseError::NoMarkedPiece),
+        };
+
+        // translate the cells to the grid of `from_ascii`
+        let mut grid = String::new();
+        for (i, &c) in cells.iter().enumerate() {
+            let tile = (i as u32 % SIZE, i as u32 / SIZE);
+            if i > 0 && tile.0 == 0 {
+                grid.push('\n');
+            }
+            grid.push(match c {
+                'o' => '.',
+                'x' => 'X',
+                // `X` would be taken for the marked piece
+                c if c.is_ascii_alphabetic() && c != 'X' => c,
+                c => return Err(ParseError::UnexpectedChar(c, tile)),
+            });
+        }
+
+        Board::from_ascii(&grid, Goal::Edge(Side::Right, row))
+    }
+
+    /// The 6x6 puzzle that the command line tool solves when it isn't
+    /// given a board, which takes 50 moves
+    pub fn sample() -> Board {
+        Board::new(
+            6,
+            6,
+            (5, 2),
+            vec![
+                Piece::marked((0, 2), 2, Direction::Horizontal),
+                Piece::new((0, 3), 2, Direction::Horizontal),
+                Piece::new((0, 4), 2, Direction::Vertical),
+                Piece::new((1, 4), 2, Direction::Vertical),
+                Piece::new((2, 0), 2, Direction::Vertical),
+                Piece::new((2, 2), 2, Direction::Vertical),
+                Piece::new((2, 4), 2, Direction::Horizontal),
+                Piece::new((2, 5), 2, Direction::Horizontal),
+                Piece::new((3, 0), 3, Direction::Horizontal),
+                Piece::new((3, 3), 2, Direction::Horizontal),
+                Piece::new((3, 1), 2, Direction::Vertical),
+                Piece::new((5, 2), 3, Direction::Vertical),
+            ],
+        )
+    }
+
+    /// Get the pieces on the board, ordered by id
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    /// Get the width, the height and the number of pieces of the board,
+    /// which boards have to share to be the same puzzle
+    pub fn signature(&self) -> (u32, u32, usize) {
+        (self.width, self.height, self.pieces.len())
+    }
+
+    /// Hash the sizes and the axes of the pieces, but not where they are,
+    /// so that boards with the same pieces get the same hash. A piece of a
+    /// single tile has no axis. The hash doesn't depend on the platform or
+    /// the run, so it can be stored.
+    pub fn shape_hash(&self) -> u64 {
+        let mut shapes: Vec<(u32, bool)> = self
+            .pieces
+            .iter()
+            .map(|p| (p.size, p.size > 1 && p.direction == Direction::Vertical))
+            .collect();
+        // the pieces are ordered by location, which the hash ignores
+        shapes.sort_unstable();
+        shapes.into_iter().fold(0, |hash, (size, vertical)| {
+            split_mix(hash ^ (u64::from(size) << 1 | vertical as u64))
+        })
+    }
+
+    /// Find all the possible moves
+    pub fn all_moves(&self) -> Vec<Move> {
+        self.all_moves_limited(u32::MAX)
+    }
+
+    /// Find all the possible moves that go a single step,
+    /// so that a solution counts every tile a piece travels
+    pub fn all_single_moves(&self) -> Vec<Move> {
+        self.all_moves_limited(1)
+    }
+
+    /// Find all the possible moves of at most `max_steps` steps, for
+    /// variants where the pieces can only slide so far in a move. With a
+    /// single step these are `all_single_moves` and without a limit
+    /// `all_moves`, in the same order.
+    pub fn all_moves_limited(&self, max_steps: u32) -> Vec<Move> {
+        let mut moves = vec![];
+        // slide from the tile at one end of a piece for as long as the
+        // tiles past it are free, a piece that goes all the way around
+        // a toroidal board stops at its own tiles
+        let mut slide = |end: Tile, towards: Side, mov: fn(usize, u32) -> Move, id| {
+            for i in 1..=max_steps {
+                match self.step(end, towards, i) {
+                    Some(tile) if self.empty_tile(tile) => moves.push(mov(id, i)),
+                    _ => break,
+                }
+            }
+        };
+
+        for piece in &self.pieces {
+            let (start, end) = self.piece_ends(piece);
+
+            if piece.moves_along(Direction::Horizontal) {
+                slide(end, Side::Right, Move::Right, piece.id);
+                slide(start, Side::Left, Move::Left, piece.id);
+            }
+            if piece.moves_along(Direction::Vertical) {
+                slide(start, Side::Top, Move::Up, piece.id);
+                slide(end, Side::Bottom, Move::Down, piece.id);
+            }
+        }
+        moves
+    }
+
+    /// Get tuples of future boards and the move to get there
+    pub fn future_boards(&self) -> Vec<(Board, Move)> {
+        self.all_moves()
+            .into_iter()
+            .map(|next| (self.play(&next), next))
+            .collect()
+    }
+
+    /// Get every board one move away like `future_boards`,
+    /// with the id of the piece that moved
+    pub fn transitions(&self) -> Vec<Transition> {
+        self.all_moves()
+            .into_iter()
+            .map(|mov| Transition {
+                board: self.play(&mov),
+                mov,
+                piece: mov.get_piece(),
+            })
+            .collect()
+    }
+
+    /// Given a move, generate a new board.
+    /// The move is assumed to be legal, see `try_play` for moves
+    /// that don't come from `all_moves`. Debug builds panic on an
+    /// illegal move, as it could take a piece past the edge of the board,
+    /// and every build panics on a move of a piece that isn't on it.
+    pub fn play(&self, mov: &Move) -> Board {
+        let mut board = self.clone();
+        board.play_in_place(mov);
+        board
+    }
+
+    /// Play a move on this board instead of creating a new one, like
+    /// `play`. Nothing is allocated in release builds, so searches can play
+    /// and undo moves on a single board. Debug builds check the move first,
+    /// which allocates.
+    pub fn play_in_place(&mut self, mov: &Move) {
+        debug_assert!(
+            self.check_move(mov).is_ok(),
+            "illegal move: {}, use try_play for moves that may be illegal",
+            mov
+        );
+        let (topology, width, height) = (self.topology, self.width, self.height);
+
+        // only the moved piece changes, so only its tiles and its part
+        // of the hash are updated
+        let p = &mut self.pieces[mov.get_piece()];
+        for t in tiles_on(topology, width, height, p) {
+            self.occupied_tiles.remove(t);
+        }
+        let from = p.location;
+        p.location = topology.0.step(from, mov.side(), mov.steps(), width, height).unwrap_or(from);
+        for t in tiles_on(topology, width, height, p) {
+            self.occupied_tiles.insert(t);
+        }
+        self.zobrist ^= zobrist_key(p.id, from) ^ zobrist_key(p.id, p.location);
+
+        // and the win can only change if a marked piece moved
+        if p.marked {
+            self.is_won = self.won(&self.pieces);
+        }
+    }
+
+    /// Play a move on this board like `play_in_place` if the move is legal,
+    /// otherwise the board is left as it was
+    pub fn try_play_in_place(&mut self, mov: &Move) -> Result<(), MoveError> {
+        self.check_move(mov)?;
+        self.play_in_place(mov);
+        Ok(())
+    }
+
+    /// Take back a move played on this board, like `undo`
+    pub fn undo_in_place(&mut self, mov: &Move) {
+        self.play_in_place(&mov.reverse());
+    }
+
+    /// Take back a move played on this board like `undo_in_place`
+    /// if it can be taken back, otherwise the board is left as it was
+    pub fn try_undo_in_place(&mut self, mov: &Move) -> Result<(), MoveError> {
+        self.try_play_in_place(&mov.reverse())
+    }
+
+    /// Check that a move can be played, i.e. that the piece exists, moves
+    /// along its axis and that every tile it passes is free
+    fn check_move(&self, mov: &Move) -> Result<(), MoveError> {
+        let id = mov.get_piece();
+        let piece = self.pieces.get(id).ok_or(MoveError::UnknownPiece(id))?;
+
+        // no piece can travel further than the length of the board along its
+        // axis, longer moves are cut short so that they still leave the board
+        // without building a path for every one of their steps
+        if !piece.moves_along(mov.axis()) {
+            return Err(MoveError::WrongDirection);
+        }
+        let length = match mov.axis() {
+            Direction::Horizontal => self.width,
+            Direction::Vertical => self.height,
+        };
+        // the tiles past the end of the piece that it moves towards
+        let (start, end) = self.piece_ends(piece);
+        let from = match mov {
+            Move::Left(..) | Move::Up(..) => start,
+            Move::Right(..) | Move::Down(..) => end,
+        };
+        let path: Vec<Option<Tile>> = (1..=mov.steps().min(length))
+            .map(|i| self.step(from, mov.side(), i))
+            .collect();
+
+        if path.is_empty() {
+            return Err(MoveError::NoSteps);
+        }
+        // tiles past the edges of the board don't exist
+        if path.iter().all(|t| t.is_some_and(|t| self.empty_tile(t))) {
+            Ok(())
+        } else {
+            Err(MoveError::Blocked)
+        }
+    }
+
+    /// Check if a move can be played, that is if it is one of `all_moves`,
+    /// without generating the other moves. See `try_play` for the reason
+    /// that a move is illegal.
+    pub fn is_legal_move(&self, mov: &Move) -> bool {
+        self.check_move(mov).is_ok()
+    }
+
+    /// Build the move of a piece by a number of steps along the given axis,
+    /// positive steps go right or down and negative ones left or up.
+    /// Returns `None` if the move can't be played.
+    pub fn make_move(&self, piece: usize, dir: Direction, steps: i32) -> Option<Move> {
+        let distance = steps.unsigned_abs();
+        let mov = match dir {
+            Direction::Horizontal if steps < 0 => Move::Left(piece, distance),
+            Direction::Horizontal => Move::Right(piece, distance),
+            Direction::Vertical if steps < 0 => Move::Up(piece, distance),
+            Direction::Vertical => Move::Down(piece, distance),
+        };
+        Some(mov).filter(|mov| self.is_legal_move(mov))
+    }
+
+    /// Find the shortest move that makes a piece cover the target tile.
+    /// Returns `None` if there is no such move, because the tile isn't on
+    /// the axis of the piece, another piece or a wall is in the way, or the
+    /// piece already covers it.
+    pub fn piece_can_reach(&self, piece: usize, target: Tile) -> Option<Move> {
+        let p = self.pieces.get(piece)?;
+        let (start, end) = self.piece_ends(p);
+        // the moves by no steps yet, from the end of the piece they go past
+        let slides = [
+            (end, Move::Right(piece, 0)),
+            (start, Move::Left(piece, 0)),
+            (start, Move::Up(piece, 0)),
+            (end, Move::Down(piece, 0)),
+        ];
+
+        for &(from, mov) in &slides {
+            if !p.moves_along(mov.axis()) {
+                continue;
+            }
+            // the piece covers the target once its end has passed onto it
+            for i in 1.. {
+                match self.step(from, mov.side(), i) {
+                    Some(tile) if self.empty_tile(tile) => {
+                        if tile == target {
+                            return Some(mov.with_steps(i));
+                        }
+                    }
+                    _ => break,
+                }
+            }
+        }
+        None
+    }
+
+    /// Given a move, generate a new board if the move is legal
+    pub fn try_play(&self, mov: &Move) -> Result<Board, MoveError> {
+        self.check_move(mov)?;
+        Ok(self.play(mov))
+    }
+
+    /// Play a list of moves in order and return the final board,
+    /// or an error naming the first move that is illegal
+    pub fn replay(&self, moves: &[Move]) -> Result<Board, ReplayError> {
+        moves
+            .iter()
+            .enumerate()
+            .try_fold(self.clone(), |board, (index, mov)| {
+                board.try_play(mov).map_err(|reason| ReplayError {
+                    index,
+                    mov: *mov,
+                    reason,
+                })
+            })
+    }
+
+    /// Play a list of moves in order and return every board along the way,
+    /// starting with this one, or an error naming the first illegal move
+    pub fn playthrough(&self, moves: &[Move]) -> Result<Vec<Board>, ReplayError> {
+        let mut boards = vec![self.clone()];
+        for (index, mov) in moves.iter().enumerate() {
+            let next = boards[index].try_play(mov).map_err(|reason| ReplayError {
+                index,
+                mov: *mov,
+                reason,
+            })?;
+            boards.push(next);
+        }
+        Ok(boards)
+    }
+
+    /// Given a move, reverse the action and return that board.
+    /// The move is assumed to have been played, like in `play`.
+    pub fn undo(&self, mov: &Move) -> Board {
+        self.play(&mov.reverse())
+    }
+
+    /// Given a move, reverse the action and return that board
+    /// if the move can be taken back
+    pub fn try_undo(&self, mov: &Move) -> Result<Board, MoveError> {
+        self.try_play(&mov.reverse())
+    }
+
+    /// Find the move that turns this board into the other one, or `None`
+    /// if no legal move does. On a toroidal board a piece can get to the
+    /// same place going either way, then there is no single move either.
+    pub fn adjacent(&self, other: &Board) -> Option<Move> {
+        let mut moves = self
+            .all_moves()
+            .into_iter()
+            .filter(|mov| self.play(mov) == *other);
+        match (moves.next(), moves.next()) {
+            (Some(mov), None) => Some(mov),
+            _ => None,
+        }
+    }
+
+    /// The tiles that the pieces moved between to get from this board to
+    /// the other one, as pairs of the tile a part of a piece was on and the
+    /// tile it is on now. Pieces are matched by their position in `pieces`.
+    pub fn diff(&self, other: &Board) -> Vec<(Tile, Tile)> {
+        self.pieces
+            .iter()
+            .zip(&other.pieces)
+            .filter(|(before, after)| before.location != after.location)
+            .flat_map(|(before, after)| self.piece_tiles(before).zip(other.piece_tiles(after)))
+            .collect()
+    }
+
+    /// Given a list of pieces and walls, find all the occupied tiles
+    /// This functions is used when initing new boards.
+    pub fn occupied_tiles(width: u32, height: u32, pieces: &[Piece], walls: &[Tile]) -> TileSet {
+        let mut tiles = TileSet::new(width, height);
+        pieces
+            .iter()
+            .flat_map(Piece::tiles)
+            .chain(walls.iter().copied())
+            .for_each(|t| tiles.insert(t));
+        tiles
+    }
+
+    /// Every won board with the same pieces and walls as this one, where
+    /// each piece can be anywhere along its row or column as long as no
+    /// pieces overlap. The boards are not necessarily reachable from this one.
+    #[cfg(feature = "std")]
+    pub(crate) fn won_boards(&self) -> Vec<Board> {
+        let mut boards = vec![];
+        let occupied = Board::occupied_tiles(self.width, self.height, &[], &self.walls);
+        self.place_pieces(0, &mut self.pieces.clone(), occupied, &mut boards);
+        boards
+    }
+
+    /// Try every location along its axis for the piece with the given index
+    /// and recurse, collecting the won boards once every piece is placed
+    #[cfg(feature = "std")]
+    fn place_pieces(
+        &self,
+        i: usize,
+        pieces: &mut Vec<Piece>,
+        occupied: TileSet,
+        boards: &mut Vec<Board>,
+    ) {
+        if i == pieces.len() {
+            let board = Board::from_pieces(
+                self.width,
+                self.height,
+                self.goal,
+                pieces.clone(),
+                self.walls.clone(),
+                self.win_mode,
+                self.topology,
+            );
+            if board.is_won {
+                boards.push(board);
+            }
+            return;
+        }
+
+        let (x, y) = self.pieces[i].location;
+        let size = self.pieces[i].size;
+        let locations: Vec<Tile> = match self.pieces[i].direction {
+            _ if self.pieces[i].frozen => vec![(x, y)],
+            // a piece of a single tile can be anywhere
+            _ if size == 1 => (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .collect(),
+            // the pieces that don't fit where they start are skipped below
+            Direction::Horizontal => (0..self.width).map(|x| (x, y)).collect(),
+            Direction::Vertical => (0..self.height).map(|y| (x, y)).collect(),
+        };
+
+        for location in locations {
+            pieces[i].location = location;
+            let tiles: Vec<Tile> = self.piece_tiles(&pieces[i]).collect();
+            if tiles.len() != size as usize || tiles.iter().any(|t| occupied.contains(t)) {
+                continue;
+            }
+            if pieces[i].marked && !self.reached_goal(&pieces[i]) {
+                continue;
+            }
+
+            let mut occupied = occupied.clone();
+            tiles.into_iter().for_each(|t| occupied.insert(t));
+            self.place_pieces(i + 1, pieces, occupied, boards);
+        }
+    }
+
+    /// Find the pieces standing between the marked piece and the goal
+    /// along the axis the marked piece moves in, closest first.
+    /// The vec is empty if the path is clear, or if the goal isn't
+    /// on the axis of the marked piece. With several marked pieces
+    /// this is the path of the first one.
+    pub fn blocking_pieces(&self) -> Vec<usize> {
+        match self.pieces.iter().find(|p| p.marked) {
+            Some(marked) => self.pieces_between(marked),
+            None => vec![],
+        }
+    }
+
+    /// Find the pieces standing between a marked piece and its goal
+    pub(crate) fn pieces_between(&self, marked: &Piece) -> Vec<usize> {
+        let (x, y) = marked.location;
+        let (goal_x, goal_y) = marked.goal.unwrap_or(self.goal);
+        // the tiles between the marked piece and the goal, closest first
+        let path: Vec<Tile> = match marked.direction {
+            Direction::Horizontal if goal_y == y && goal_x > x => {
+                (x + marked.size..=goal_x).map(|i| (i, y)).collect()
+            }
+            Direction::Horizontal if goal_y == y => (goal_x..x).rev().map(|i| (i, y)).collect(),
+            Direction::Vertical if goal_x == x && goal_y > y => {
+                (y + marked.size..=goal_y).map(|i| (x, i)).collect()
+            }
+            Direction::Vertical if goal_x == x => (goal_y..y).rev().map(|i| (x, i)).collect(),
+            _ => vec![],
+        };
+
+        let mut blocking = vec![];
+        for tile in path {
+            if let Some(id) = self.piece_at(tile) {
+                if !blocking.contains(&id) {
+                    blocking.push(id);
+                }
+            }
+        }
+        blocking
+    }
+
+    /// Cheaply check if the board can never be won, without searching.
+    /// This is only the case when a marked piece can't slide to its goal
+    /// at all, because the piece is frozen, the goal isn't on the board,
+    /// isn't in the row or column the piece moves along or because a wall or
+    /// a frozen piece is in the way. A board that passes the check may still
+    /// be unsolvable. The solvers give up right away on boards that fail it.
+    pub fn quick_unsolvable(&self) -> bool {
+        // the goal may be around the edge on other topologies
+        if !self.topology.0.is_rectangular() {
+            return false;
+        }
+        self.pieces
+            .iter()
+            .filter(|p| p.marked && !self.reached_goal(p))
+            .any(|p| !self.goal_in_line(p))
+    }
+
+    /// Check if every marked piece could reach its goal if all the pieces
+    /// that can move were out of the way. Walls and frozen pieces can never
+    /// be cleared, so they still count. The board can't be won if this is
+    /// false, see `quick_unsolvable`.
+    pub fn goal_reachable_ignoring_blockers(&self) -> bool {
+        !self.quick_unsolvable()
+    }
+
+    /// Check if the goal of a marked piece is on the board, in line with
+    /// the piece and without any walls or frozen pieces between them
+    fn goal_in_line(&self, marked: &Piece) -> bool {
+        let (x, y) = marked.location;
+        let goal = marked.goal.unwrap_or(self.goal);
+        if marked.frozen {
+            return false;
+        }
+        // a piece of a single tile can move around the walls
+        if marked.size == 1 {
+            return self.tile_exists(goal);
+        }
+        let (goal_x, goal_y) = goal;
+        let (along, goal_along) = match marked.direction {
+            Direction::Horizontal if goal_y == y => (x, goal_x),
+            Direction::Vertical if goal_x == x => (y, goal_y),
+            _ => return false,
+        };
+        let tile = |i| match marked.direction {
+            Direction::Horizontal => (i, y),
+            Direction::Vertical => (x, i),
+        };
+
+        let frozen = |t| self.piece_at(t).is_some_and(|id| self.pieces[id].frozen);
+        self.tile_exists(goal)
+            && (along.min(goal_along)..=along.max(goal_along))
+                .map(tile)
+                .all(|t| !self.walls.contains(&t) && !frozen(t))
+    }
+
+    /// A lower bound on the number of moves left to win, a marked piece
+    /// that hasn't reached its goal has to move and so does every piece
+    /// between it and the goal, unless it is a single tile that can go
+    /// around them or the board isn't rectangular
+    #[cfg(feature = "std")]
+    pub(crate) fn min_moves_left(&self) -> u32 {
+        let rectangular = self.topology.0.is_rectangular();
+        self.pieces
+            .iter()
+            .filter(|p| p.marked && !self.reached_goal(p))
+            .map(|p| match p.size {
+                _ if !rectangular => 1,
+                1 => 1,
+                _ => 1 + self.pieces_between(p).len() as u32,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Mirror the board left to right and/or top to bottom, moving
+    /// the pieces, walls and goals along with it
+    pub(crate) fn transformed(&self, flip_x: bool, flip_y: bool) -> Board {
+        let (width, height) = (self.width, self.height);
+        let flip = |(x, y): Tile| {
+            (
+                if flip_x { width - 1 - x } else { x },
+                if flip_y { height - 1 - y } else { y },
+            )
+        };
+
+        let mut pieces: Vec<Piece> = self
+            .pieces
+            .iter()
+            .map(|p| {
+                // the far end of the piece becomes its location
+                // when the piece is turned around
+                let (start, end) = self.piece_ends(p);
+                let location = if p.turned_around(flip_x, flip_y) { end } else { start };
+                Piece {
+                    location: flip(location),
+                    goal: p.goal.map(flip),
+                    ..p.clone()
+                }
+            })
+            .collect();
+        let mut walls: Vec<Tile> = self.walls.iter().copied().map(flip).collect();
+        // numbered again like in `try_with_walls`, so that the board
+        // equals the one built from the mirrored pieces
+        pieces.sort_by_key(|p| row_major(p.location));
+        walls.sort_by_key(|&wall| row_major(wall));
+        // the ends of a marked piece swap places when its axis is flipped
+        let win_mode = if self.pieces.iter().any(|p| p.marked && p.turned_around(flip_x, flip_y)) {
+            self.win_mode.flipped()
+        } else {
+            self.win_mode
+        };
+
+        Board::from_pieces(width, height, flip(self.goal), pieces, walls, win_mode, self.topology)
+    }
+
+    /// Check if the transformed board is as hard to solve as this one,
+    /// which is not the case when the win mode depends on the ends of the
+    /// marked pieces and only some of them are turned around
+    fn keeps_win_mode(&self, flip_x: bool, flip_y: bool) -> bool {
+        let mut turned = self
+            .pieces
+            .iter()
+            .filter(|p| p.marked)
+            .map(|p| p.turned_around(flip_x, flip_y));
+        match turned.next() {
+            Some(first) if self.win_mode != WinMode::AnyCell => turned.all(|t| t == first),
+            _ => true,
+        }
+    }
+
+    /// Mirror the board left to right. The pieces keep their directions,
+    /// so the mirror is as hard to solve as the board, and are numbered
+    /// again in row-major order like in `try_new`. With
+    /// `WinMode::Anchor` or `WinMode::FarEnd` the two are swapped when a
+    /// marked piece is turned around, which only keeps the difficulty if
+    /// every marked piece is.
+    pub fn mirror_horizontal(&self) -> Board {
+        self.transformed(true, false)
+    }
+
+    /// Mirror the board top to bottom, like `mirror_horizontal`
+    pub fn mirror_vertical(&self) -> Board {
+        self.transformed(false, true)
+    }
+
+    /// Rotate the board half a turn, like `mirror_horizontal`
+    pub fn rotate_180(&self) -> Board {
+        self.transformed(true, true)
+    }
+
+    /// Get the board with every piece moved to the given location,
+    /// indexed by id. The locations are assumed to be valid.
+    pub(crate) fn with_locations(&self, locations: &[Tile]) -> Board {
+        let pieces = self
+            .pieces
+            .iter()
+            .zip(locations)
+            .map(|(p, &location)| Piece {
+                location,
+                ..p.clone()
+            })
+            .collect();
+        let walls = self.walls.clone();
+        let (width, height) = (self.width, self.height);
+        Board::from_pieces(width, height, self.goal, pieces, walls, self.win_mode, self.topology)
+    }
+
+    /// Get the canonical form of the board among its mirror images.
+    /// Boards that are mirror images of each other, goals included,
+    /// have the same canonical form, whatever the order of their pieces.
+    /// Mirror images that are not as hard to solve because of the win mode
+    /// are left out.
+    pub fn canonical(&self) -> Board {
+        // the pieces are compared as a set, not by id
+        let key = |b: &Board| {
+            let mut walls = b.walls.clone();
+            walls.sort_unstable();
+            let mut pieces: Vec<_> = b
+                .pieces
+                .iter()
+                .map(|p| {
+                    let vertical = p.direction == Direction::Vertical;
+                    (p.location, p.size, vertical, p.marked, p.frozen, p.goal)
+                })
+                .collect();
+            pieces.sort_unstable();
+            (b.goal, pieces, walls)
+        };
+
+        // not transforming it at all numbers the pieces again too
+        [(true, false), (false, true), (true, true)]
+            .iter()
+            .filter(|&&(flip_x, flip_y)| self.keeps_win_mode(flip_x, flip_y))
+            .map(|&(flip_x, flip_y)| self.transformed(flip_x, flip_y))
+            .fold(self.transformed(false, false), |best, b| {
+                if key(&b) < key(&best) {
+                    b
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Get the id of the piece that covers the tile, or `None` if the tile
+    /// is empty, a wall or not on the board. Free tiles are ruled out with
+    /// the occupied tiles, the pieces are only searched for taken ones.
+    pub fn piece_at(&self, tile: Tile) -> Option<usize> {
+        if !self.occupied_tiles.contains(&tile) {
+            return None;
+        }
+        self.pieces
+            .iter()
+            .find(|p| self.piece_tiles(p).any(|t| t == tile))
+            .map(|p| p.id)
+    }
+
+    /// Check if a tile is free
+    pub fn empty_tile(&self, t: Tile) -> bool {
+        self.tile_exists(t) && !self.occupied_tiles.contains(&t)
+    }
+
+    /// Check if a tile exists on the board, with a rectangular topology
+    /// i.e has a lower value than the width/height and, greater or equal to 0
+    pub fn tile_exists(&self, tile: Tile) -> bool {
+        self.topology.0.contains(tile, self.width, self.height)
+    }
+
+    /// Check that the parts of the board that follow from its pieces agree
+    /// with them, for tests and debugging. Every piece has to be on the
+    /// board without overlapping a wall or another piece and have its index
+    /// as its id, and the occupied tiles, the win flag and the hash have to
+    /// be what they would be if they were computed again. Returns what is
+    /// wrong otherwise. It takes time linear in the size of the board.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let mut occupied = TileSet::new(self.width, self.height);
+        for &wall in &self.walls {
+            if !self.tile_exists(wall) {
+                return Err(format!("the wall at {:?} is not on the board", wall));
+            }
+            occupied.insert(wall);
+        }
+
+        for (i, piece) in self.pieces.iter().enumerate() {
+            if piece.id != i {
+                return Err(format!("piece {} has the id {}", i, piece.id));
+            }
+            if piece.size == 0 {
+                return Err(format!("piece {} has a size of zero", i));
+            }
+            let tiles: Vec<Tile> = self.piece_tiles(piece).collect();
+            if tiles.len() != piece.size as usize {
+                return Err(format!("piece {} extends off the board", i));
+            }
+            for tile in tiles {
+                if occupied.contains(&tile) {
+                    return Err(format!("piece {} overlaps something at {:?}", i, tile));
+                }
+                occupied.insert(tile);
+            }
+        }
+
+        if occupied != self.occupied_tiles {
+            return Err("the occupied tiles don't match the pieces and walls".into());
+        }
+        if self.won(&self.pieces) != self.is_won {
+            return Err(format!("the win flag is {} but should be {}", self.is_won, !self.is_won));
+        }
+        let zobrist = self
+            .pieces
+            .iter()
+            .fold(0, |hash, p| hash ^ zobrist_key(p.id, p.location));
+        if zobrist != self.zobrist {
+            return Err("the hash doesn't match the locations of the pieces".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn marked_piece_count() {
+        let piece = |x, marked| {
+            if marked {
+                Piece::marked((x, 0), 1, Direction::Horizontal)
+            } else {
+                Piece::new((x, 0), 1, Direction::Horizontal)
+            }
+        };
+        let count = |pieces| Board::try_new(4, 1, (3, 0), pieces).map(|_| ());
+        assert_eq!(count(vec![piece(0, false)]), Err(BoardError::MarkedPieceCount(0)));
+        assert_eq!(
+            count(vec![piece(0, true), piece(1, true)]),
+            Err(BoardError::MarkedPieceCount(2))
+        );
+        assert_eq!(count(vec![piece(0, true), piece(1, false)]), Ok(()));
+
+        // the other marked pieces can have goals of their own
+        let with_goal = Piece::marked_with_goal((1, 0), 1, Direction::Horizontal, (2, 0));
+        assert_eq!(count(vec![piece(0, true), with_goal]), Ok(()));
+    }
+
+    #[test]
+    fn ascii_without_a_marked_piece() {
+        assert_eq!(Board::from_ascii("aa.\n...", (2, 0)), Err(ParseError::NoMarkedPiece));
+        assert!(Board::from_ascii("aaX\n...", (2, 0)).is_ok());
+    }
+
+    #[test]
+    fn quick_unsolvable_is_sound() {
+        assert!(!Board::sample().quick_unsolvable());
+
+        let marked = |direction| Piece::marked((0, 0), 2, direction);
+        let perpendicular = Board::new(4, 4, (3, 0), vec![marked(Direction::Vertical)]);
+        assert!(perpendicular.quick_unsolvable());
+        assert!(!perpendicular.goal_reachable_ignoring_blockers());
+
+        // pieces that can move out of the way don't count
+        let blocker = Piece::new((2, 0), 2, Direction::Vertical);
+        let blocked = Board::new(4, 4, (3, 0), vec![marked(Direction::Horizontal), blocker]);
+        assert!(!blocked.quick_unsolvable());
+
+        let walled = vec![marked(Direction::Horizontal)];
+        let walled = Board::try_with_walls(4, 4, (3, 0), walled, vec![(2, 0)]).unwrap();
+        assert!(walled.quick_unsolvable());
+
+        // a single tile can go around the wall
+        let single = vec![Piece::marked((0, 0), 1, Direction::Horizontal)];
+        let single = Board::try_with_walls(4, 4, (3, 0), single, vec![(2, 0)]).unwrap();
+        assert!(!single.quick_unsolvable());
+    }
+
+    #[test]
+    fn right_edge_exit() {
+        let pieces = vec![
+            Piece::marked((0, 2), 2, Direction::Horizontal),
+            Piece::new((3, 1), 2, Direction::Vertical),
+        ];
+        let board = Board::new(6, 6, (6, 2), pieces);
+        assert_eq!(board.goal, (5, 2));
+        assert!(!board.is_won);
+
+        // the blocking piece comes first in row-major order
+        let board = board.play(&Move::Up(0, 1)).play(&Move::Right(1, 4));
+        assert!(board.is_won);
+    }
+
+    #[test]
+    fn bottom_edge_exit() {
+        let pieces = vec![Piece::marked((2, 0), 3, Direction::Vertical)];
+        let board = Board::new(6, 6, (2, 6), pieces);
+        assert_eq!(board.goal, (2, 5));
+        assert!(board.play(&Move::Down(0, 3)).is_won);
+    }
+
+    #[test]
+    fn exit_past_a_corner_is_off_the_board() {
+        let pieces = vec![Piece::marked((0, 0), 2, Direction::Horizontal)];
+        assert_eq!(
+            Board::try_new(6, 6, (6, 6), pieces),
+            Err(BoardError::GoalOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn goal_out_of_bounds() {
+        let marked = || Piece::marked((0, 0), 2, Direction::Horizontal);
+        assert_eq!(
+            Board::try_new(6, 6, (99, 0), vec![marked()]),
+            Err(BoardError::GoalOutOfBounds)
+        );
+
+        let with_goal = |goal| Piece::marked_with_goal((0, 1), 2, Direction::Horizontal, goal);
+        assert_eq!(
+            Board::try_new(6, 6, (5, 0), vec![marked(), with_goal((99, 0))]),
+            Err(BoardError::GoalOutOfBounds)
+        );
+
+        // a gap in the wall is normalized to the tile next to it
+        let board = Board::try_new(6, 6, (5, 0), vec![marked(), with_goal((6, 1))]).unwrap();
+        assert_eq!(board.pieces()[1].goal(), Some((5, 1)));
+        assert_eq!(board.mirror_horizontal().pieces()[1].goal(), Some((0, 1)));
+    }
+
+    /// The sample board mirrored left to right by hand
+    fn mirrored_sample() -> Board {
+        Board::new(
+            6,
+            6,
+            (0, 2),
+            vec![
+                Piece::marked((4, 2), 2, Direction::Horizontal),
+                Piece::new((4, 3), 2, Direction::Horizontal),
+                Piece::new((5, 4), 2, Direction::Vertical),
+                Piece::new((4, 4), 2, Direction::Vertical),
+                Piece::new((3, 0), 2, Direction::Vertical),
+                Piece::new((3, 2), 2, Direction::Vertical),
+                Piece::new((2, 4), 2, Direction::Horizontal),
+                Piece::new((2, 5), 2, Direction::Horizontal),
+                Piece::new((0, 0), 3, Direction::Horizontal),
+                Piece::new((1, 3), 2, Direction::Horizontal),
+                Piece::new((2, 1), 2, Direction::Vertical),
+                Piece::new((0, 2), 3, Direction::Vertical),
+            ],
+        )
+    }
+
+    #[test]
+    fn mirror_equals_the_board_built_mirrored() {
+        let board = Board::sample();
+        assert_eq!(board.mirror_horizontal(), mirrored_sample());
+        assert_eq!(board.mirror_horizontal().mirror_horizontal(), board);
+        assert_eq!(board.rotate_180().rotate_180(), board);
+        assert!(board.rotate_180().check_invariants().is_ok());
+    }
+
+    #[test]
+    fn mirrors_canonicalize_equal() {
+        let board = Board::sample();
+        assert_eq!(board.canonical(), mirrored_sample().canonical());
+        assert_eq!(board.canonical(), board.mirror_vertical().canonical());
+        assert_eq!(board.canonical(), board.rotate_180().canonical());
+
+        // pieces that moved keep their ids, which differ from a new board's
+        let played = board.play(&Move::Down(5, 1));
+        let rebuilt = Board::new(6, 6, (5, 2), played.pieces().to_vec());
+        assert_ne!(played.pieces(), rebuilt.pieces());
+        assert_eq!(played.canonical(), rebuilt.canonical());
+        assert_ne!(played.canonical(), board.canonical());
+    }
+
+    #[test]
+    fn piece_order_doesnt_matter() {
+        let mut pieces = Board::sample().pieces().to_vec();
+        pieces.reverse();
+        let reversed = Board::new(6, 6, (5, 2), pieces);
+        assert_eq!(reversed, Board::sample());
+        assert_eq!(reversed.to_string(), Board::sample().to_string());
+    }
+
+    #[test]
+    fn empty_board_has_no_exit() {
+        assert_eq!(Board::try_new(0, 1, (0, 0), vec![]), Err(BoardError::GoalOutOfBounds));
+        assert_eq!(Board::try_new(1, 0, (0, 0), vec![]), Err(BoardError::GoalOutOfBounds));
+        assert!(Board::from_bytes(&[0, 1, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let board = Board::sample();
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+
+        // the skipped fields are computed again
+        let played = board.play(&Move::Down(5, 1));
+        let json = serde_json::to_string(&played).unwrap();
+        let read: Board = serde_json::from_str(&json).unwrap();
+        assert!(read.check_invariants().is_ok());
+        // the pieces are numbered again like on a new board
+        assert_eq!(read, Board::new(6, 6, (5, 2), played.pieces().to_vec()));
+
+        let mov = Move::Left(3, 2);
+        let json = serde_json::to_string(&mov).unwrap();
+        assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), mov);
+    }
+
+    #[test]
+    fn walls_block_the_pieces() {
+        let pieces = vec![
+            Piece::marked((0, 0), 2, Direction::Horizontal),
+            Piece::new((0, 1), 1, Direction::Horizontal),
+        ];
+        let board = Board::try_with_walls(5, 2, (4, 0), pieces, vec![(2, 0), (3, 1)]).unwrap();
+        assert!(!board.empty_tile((2, 0)));
+        assert!(board.all_moves().iter().all(|m| m.get_piece() != 0));
+        assert_eq!(board.all_moves(), vec![Move::Right(1, 1), Move::Right(1, 2)]);
+
+        // the walls stay where they are when pieces move
+        let played = board.play(&Move::Right(1, 2));
+        assert_eq!(played.walls, board.walls);
+        assert_eq!(played.undo(&Move::Right(1, 2)), board);
+        assert_eq!(
+            Board::try_with_walls(5, 2, (4, 0), vec![], vec![(5, 0)]),
+            Err(BoardError::WallOutOfBounds(0))
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(crate::solve_path(board), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn replay_the_bfs_solution() {
+        let board = Board::sample();
+        let solution = crate::solve_path(board.clone()).unwrap();
+        assert!(board.replay(&solution).unwrap().is_won);
+        assert_eq!(board.replay(&[]), Ok(board));
+    }
+
+    #[test]
+    fn replay_errors_name_the_move() {
+        let board = Board::sample();
+        let error = |moves: &[Move]| board.replay(moves).unwrap_err();
+        let moves = [Move::Up(5, 1), Move::Right(3, 1)];
+        assert_eq!(
+            error(&moves),
+            ReplayError {
+                index: 1,
+                mov: Move::Right(3, 1),
+                reason: MoveError::Blocked
+            }
+        );
+        assert_eq!(error(&[Move::Up(12, 1)]).reason, MoveError::UnknownPiece(12));
+        assert_eq!(error(&[Move::Up(3, 1)]).reason, MoveError::WrongDirection);
+        assert_eq!(error(&[Move::Down(5, 0)]).reason, MoveError::NoSteps);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn two_marked_pieces_both_have_to_arrive() {
+        // the vertical car has to get out of the way of the horizontal one
+        // and reach the bottom row itself
+        let pieces = vec![
+            Piece::marked_with_goal((0, 1), 2, Direction::Horizontal, (3, 1)),
+            Piece::marked((2, 0), 2, Direction::Vertical),
+        ];
+        let board = Board::new(4, 4, (2, 3), pieces);
+        let moved_down = board.play(&Move::Down(0, 2));
+        assert!(!moved_down.is_won);
+        assert!(moved_down.play(&Move::Right(1, 2)).is_won);
+
+        let solution = crate::solve_path(board.clone()).unwrap();
+        assert_eq!(solution, vec![Move::Down(0, 2), Move::Right(1, 2)]);
+    }
+
+    #[test]
+    fn wide_boards() {
+        let pieces = vec![
+            Piece::new((0, 0), 7, Direction::Horizontal),
+            Piece::new((7, 0), 3, Direction::Vertical),
+            Piece::marked((0, 2), 2, Direction::Horizontal),
+        ];
+        let board = Board::new(8, 6, (7, 2), pieces);
+        assert!(board.tile_exists((7, 5)));
+        assert!(!board.tile_exists((8, 0)));
+        assert!(!board.tile_exists((0, 6)));
+
+        // the long piece is stuck until the corner is free
+        let mut moves = board.all_moves();
+        moves.sort();
+        let mut expected: Vec<Move> = (1..=5).map(|steps| Move::Right(2, steps)).collect();
+        expected.extend((1..=3).map(|steps| Move::Down(1, steps)));
+        expected.sort();
+        assert_eq!(moves, expected);
+
+        let board = board.play(&Move::Down(1, 2));
+        assert!(board.is_legal_move(&Move::Right(0, 1)));
+        assert!(!board.is_legal_move(&Move::Right(0, 2)));
+        assert!(!board.play(&Move::Right(2, 5)).is_won);
+        assert!(!board.is_legal_move(&Move::Right(2, 6)));
+        let board = board.play(&Move::Down(1, 1));
+        assert!(board.play(&Move::Right(2, 6)).is_won);
+        assert!(!board.is_legal_move(&Move::Right(2, 7)));
+    }
+
+    #[test]
+    fn moves_at_the_edge() {
+        let board = Board::sample();
+        // the marked piece is at the left edge
+        assert_eq!(board.try_play(&Move::Left(3, 1)), Err(MoveError::Blocked));
+        assert_eq!(board.try_play(&Move::Left(3, u32::MAX)), Err(MoveError::Blocked));
+        assert_eq!(board.try_play(&Move::Right(11, u32::MAX)), Err(MoveError::Blocked));
+
+        // the bottom piece can slide right up to the edge, but not past it
+        let at_edge = board.try_play(&Move::Right(11, 2)).unwrap();
+        assert_eq!(at_edge.pieces()[11].location(), (4, 5));
+        assert_eq!(board.try_play(&Move::Right(11, 3)), Err(MoveError::Blocked));
+        assert_eq!(at_edge.try_undo(&Move::Right(11, 2)), Ok(board));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "illegal move")]
+    fn playing_off_the_board_panics_clearly() {
+        Board::sample().play(&Move::Left(3, 1));
+    }
+
+    /// Hash a board with FNV-1a, so that the test doesn't need std
+    fn hash_of(board: &Board) -> u64 {
+        struct Fnv(u64);
+        impl Hasher for Fnv {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+            fn write(&mut self, bytes: &[u8]) {
+                for &byte in bytes {
+                    self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3);
+                }
+            }
+        }
+        let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+        board.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_boards_hash_equal() {
+        let board = Board::sample();
+        assert_eq!(hash_of(&board), hash_of(&Board::sample()));
+
+        // the same board reached by moves in another order
+        let one_way = board.play(&Move::Up(5, 1)).play(&Move::Right(10, 1));
+        let other_way = board.play(&Move::Right(10, 1)).play(&Move::Up(5, 1));
+        assert_eq!(one_way, other_way);
+        assert_eq!(hash_of(&one_way), hash_of(&other_way));
+        assert_ne!(hash_of(&one_way), hash_of(&board));
+
+        // playing a move and taking it back gives the hash back too
+        let mut played = board.clone();
+        played.play_in_place(&Move::Down(5, 1));
+        played.undo_in_place(&Move::Down(5, 1));
+        assert_eq!(hash_of(&played), hash_of(&board));
+        assert!(played.check_invariants().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn transformed_boards_take_as_many_moves() {
+        let board = Board::sample();
+        let moves = crate::solve_path(board.clone()).unwrap().len();
+        let transformed = [
+            board.mirror_horizontal(),
+            board.mirror_vertical(),
+            board.rotate_180(),
+        ];
+        for transformed in transformed {
+            assert!(transformed.check_invariants().is_ok());
+            assert_eq!(transformed.walls.len(), board.walls.len());
+            let solution = crate::solve_path(transformed.clone()).unwrap();
+            assert_eq!(solution.len(), moves);
+            assert!(transformed.replay(&solution).unwrap().is_won);
+        }
+    }
+
+    #[test]
+    fn red_car_exits_the_right_edge() {
+        let grid = "\
+            ..a...\n\
+            ..a...\n\
+            XXa.b.\n\
+            ....b.\n\
+            ......\n\
+            ......";
+        let board = Board::from_ascii(grid, Goal::Edge(Side::Right, 2)).unwrap();
+        assert_eq!(board.goal, (5, 2));
+        assert!(!board.is_won);
+        #[cfg(feature = "std")]
+        assert_eq!(crate::solve_path(board.clone()).map(|s| s.len()), Some(3));
+
+        // the car wins once it reaches the edge, wherever the goal tile is
+        let board = board.play(&Move::Down(0, 3)).play(&Move::Down(2, 2));
+        assert!(!board.is_won);
+        assert!(board.play(&Move::Right(1, 4)).is_won);
+
+        let pieces = vec![Piece::marked((2, 1), 2, Direction::Horizontal)];
+        let board = Board::new(4, 3, Goal::Edge(Side::Left, 1), pieces);
+        assert_eq!(board.goal, (0, 1));
+        assert!(board.play(&Move::Left(0, 2)).is_won);
+    }
+
+    #[test]
+    fn moves_are_ordered_by_direction_piece_and_steps() {
+        let mut moves = vec![
+            Move::Down(0, 1),
+            Move::Up(2, 1),
+            Move::Left(3, 2),
+            Move::Right(1, 1),
+            Move::Left(3, 1),
+            Move::Left(0, 5),
+        ];
+        moves.sort();
+        assert_eq!(
+            moves,
+            [
+                Move::Left(0, 5),
+                Move::Left(3, 1),
+                Move::Left(3, 2),
+                Move::Right(1, 1),
+                Move::Up(2, 1),
+                Move::Down(0, 1),
+            ]
+        );
+
+        let mut sample_moves = Board::sample().all_moves();
+        sample_moves.sort();
+        assert_eq!(
+            sample_moves,
+            [
+                Move::Right(10, 1),
+                Move::Right(11, 1),
+                Move::Right(11, 2),
+                Move::Up(5, 1),
+                Move::Down(5, 1),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn playthrough_of_a_solution() {
+        let board = Board::sample();
+        let solution = crate::solve_path(board.clone()).unwrap();
+        let boards = board.playthrough(&solution).unwrap();
+        assert_eq!(boards.len(), solution.len() + 1);
+        assert_eq!(boards[0], board);
+        assert!(boards.last().unwrap().is_won);
+        assert!(boards[..solution.len()].iter().all(|b| !b.is_won));
+        for (i, mov) in solution.iter().enumerate() {
+            assert_eq!(boards[i].play(mov), boards[i + 1]);
+        }
+
+        let error = board.playthrough(&[solution[0], Move::Left(3, 1)]).unwrap_err();
+        assert_eq!((error.index, error.reason), (1, MoveError::Blocked));
+    }
+
+    #[test]
+    fn transitions_name_the_moved_piece() {
+        let board = Board::sample();
+        let transitions = board.transitions();
+        let futures = board.future_boards();
+        assert_eq!(transitions.len(), futures.len());
+        for (transition, (future, mov)) in transitions.iter().zip(futures) {
+            assert_eq!((&transition.board, transition.mov), (&future, mov));
+            // only the piece that moved is somewhere else
+            let moved: Vec<usize> = (0..board.pieces().len())
+                .filter(|&id| board.pieces()[id].location() != future.pieces()[id].location())
+                .collect();
+            assert_eq!(moved, [transition.piece]);
+        }
+    }
+
+    #[test]
+    fn win_modes() {
+        let pieces = vec![Piece::marked((0, 0), 3, Direction::Horizontal)];
+        let board = Board::new(6, 1, (1, 0), pieces);
+        assert_eq!(board.win_mode(), WinMode::AnyCell);
+        let won = |mode, steps| {
+            let board = board.clone().with_win_mode(mode);
+            match steps {
+                0 => board.is_won,
+                _ => board.play(&Move::Right(0, steps)).is_won,
+            }
+        };
+
+        // the piece covers the goal with its middle tile
+        assert!(won(WinMode::AnyCell, 0));
+        assert!(!won(WinMode::Anchor, 0));
+        assert!(!won(WinMode::FarEnd, 0));
+        // and then with its left tile
+        assert!(won(WinMode::AnyCell, 1));
+        assert!(won(WinMode::Anchor, 1));
+        assert!(!won(WinMode::FarEnd, 1));
+        // and then not at all
+        assert!(!won(WinMode::AnyCell, 2));
+        assert!(!won(WinMode::Anchor, 2));
+
+        let pieces = vec![Piece::marked((0, 0), 3, Direction::Horizontal)];
+        let board = Board::new(6, 1, (2, 0), pieces).with_win_mode(WinMode::FarEnd);
+        assert!(board.is_won);
+    }
+
+    #[test]
+    fn single_tiles_move_both_ways() {
+        let pieces = vec![Piece::marked((1, 1), 1, Direction::Horizontal)];
+        let board = Board::new(3, 3, (2, 2), pieces);
+        assert_eq!(board.pieces()[0].occupies(), [(1, 1)]);
+        let mut moves = board.all_moves();
+        moves.sort();
+        assert_eq!(
+            moves,
+            [Move::Left(0, 1), Move::Right(0, 1), Move::Up(0, 1), Move::Down(0, 1)]
+        );
+
+        // the direction it was made with doesn't matter
+        let pieces = vec![Piece::marked((1, 1), 1, Direction::Vertical)];
+        assert_eq!(Board::new(3, 3, (2, 2), pieces).all_moves().len(), 4);
+
+        let board = board.play(&Move::Right(0, 1)).play(&Move::Down(0, 1));
+        assert!(board.is_won);
+
+        // a tile in a corner turns once to reach the opposite corner
+        let pieces = vec![Piece::marked((0, 0), 1, Direction::Horizontal)];
+        let corner = Board::new(3, 3, (2, 2), pieces);
+        assert!(!corner.is_won);
+        #[cfg(feature = "std")]
+        assert_eq!(crate::solve_path(corner).map(|s| s.len()), Some(2));
+    }
+
+    #[test]
+    fn long_pieces() {
+        let pieces = vec![
+            Piece::marked((0, 0), 4, Direction::Horizontal),
+            Piece::new((6, 1), 5, Direction::Vertical),
+        ];
+        let board = Board::new(7, 6, (6, 0), pieces);
+        assert_eq!(board.pieces()[0].occupies(), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(board.pieces()[1].occupies().len(), 5);
+        let mut moves = board.all_moves();
+        moves.sort();
+        assert_eq!(
+            moves,
+            [Move::Right(0, 1), Move::Right(0, 2), Move::Right(0, 3), Move::Up(1, 1)]
+        );
+        assert!(!board.play(&Move::Right(0, 2)).is_won);
+        assert!(board.play(&Move::Right(0, 3)).is_won);
+        assert!(board.play(&Move::Up(1, 1)).try_play(&Move::Right(0, 3)).is_err());
+    }
+
+    #[test]
+    fn sample_board() {
+        let board = Board::sample();
+        let drawing = "..abbb\n..ac..\nXXec.f\nggehhf\nijkk.f\nijll..";
+        assert_eq!(board.to_string(), drawing);
+        assert_eq!(Board::from_ascii(drawing, (5, 2)), Ok(board.clone()));
+        assert_eq!((board.width, board.height, board.goal), (6, 6, (5, 2)));
+        assert_eq!(board.pieces().len(), 12);
+        assert!(!board.is_won);
+    }
+
+    #[test]
+    fn piece_at_the_tiles_of_a_piece() {
+        let board = Board::sample();
+        for piece in board.pieces() {
+            for tile in board.piece_tiles(piece) {
+                assert_eq!(board.piece_at(tile), Some(piece.id()));
+            }
+        }
+        assert_eq!(board.piece_at((0, 0)), None);
+        assert_eq!(board.piece_at((6, 0)), None);
+        assert_eq!(board.piece_at((0, u32::MAX)), None);
+
+        // the piece goes with it when it moves
+        let board = board.play(&Move::Up(5, 1));
+        assert_eq!(board.piece_at((5, 1)), Some(5));
+        assert_eq!(board.piece_at((5, 4)), None);
+
+        let pieces = vec![Piece::marked((0, 0), 2, Direction::Horizontal)];
+        let walled = Board::try_with_walls(4, 2, (3, 0), pieces, vec![(2, 1)]).unwrap();
+        assert_eq!(walled.piece_at((2, 1)), None);
+        assert_eq!(walled.piece_at((1, 0)), Some(0));
+    }
+
+    #[test]
+    fn legal_moves_are_the_generated_ones() {
+        let board = Board::sample();
+        assert!(board.is_legal_move(&Move::Up(5, 1)));
+        // `b` is in the way of `c`, and `e` of the marked piece
+        assert!(!board.is_legal_move(&Move::Up(2, 1)));
+        assert!(!board.is_legal_move(&Move::Right(3, 1)));
+        // unknown pieces and moves of no steps aren't legal either
+        assert!(!board.is_legal_move(&Move::Up(12, 1)));
+        assert!(!board.is_legal_move(&Move::Up(5, 0)));
+
+        let all_moves = board.all_moves();
+        for id in 0..board.pieces().len() {
+            for steps in 0..8 {
+                let moves = [
+                    Move::Left(id, steps),
+                    Move::Right(id, steps),
+                    Move::Up(id, steps),
+                    Move::Down(id, steps),
+                ];
+                for mov in &moves {
+                    assert_eq!(board.is_legal_move(mov), all_moves.contains(mov), "{}", mov);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn diff_of_a_move() {
+        let board = Board::sample();
+        let moved = board.play(&Move::Up(5, 1));
+        assert_eq!(
+            board.diff(&moved),
+            [((5, 2), (5, 1)), ((5, 3), (5, 2)), ((5, 4), (5, 3))]
+        );
+        assert_eq!(
+            moved.diff(&board),
+            [((5, 1), (5, 2)), ((5, 2), (5, 3)), ((5, 3), (5, 4))]
+        );
+        assert!(board.diff(&board).is_empty());
+    }
+
+    #[test]
+    fn goal_reachable_ignoring_blockers() {
+        assert!(Board::sample().goal_reachable_ignoring_blockers());
+
+        let marked = |size| vec![Piece::marked((0, 1), size, Direction::Horizontal)];
+        // the goal is off the row of the marked piece
+        assert!(!Board::new(5, 3, (4, 2), marked(2)).goal_reachable_ignoring_blockers());
+        // a single tile can turn
+        assert!(Board::new(5, 3, (4, 2), marked(1)).goal_reachable_ignoring_blockers());
+        // a wall can't be cleared, but a piece can
+        let walled = Board::try_with_walls(5, 3, (4, 1), marked(2), vec![(3, 1)]).unwrap();
+        assert!(!walled.goal_reachable_ignoring_blockers());
+        let mut pieces = marked(2);
+        pieces.push(Piece::new((3, 0), 2, Direction::Vertical));
+        assert!(Board::new(5, 3, (4, 1), pieces).goal_reachable_ignoring_blockers());
+    }
+
+    #[test]
+    fn make_move_from_an_axis_and_steps() {
+        let board = Board::sample();
+        assert_eq!(board.make_move(5, Direction::Vertical, -1), Some(Move::Up(5, 1)));
+        assert_eq!(board.make_move(5, Direction::Vertical, 1), Some(Move::Down(5, 1)));
+        assert_eq!(board.make_move(11, Direction::Horizontal, 2), Some(Move::Right(11, 2)));
+        // blocked, along the wrong axis and of an unknown piece
+        assert_eq!(board.make_move(11, Direction::Horizontal, 3), None);
+        assert_eq!(board.make_move(11, Direction::Horizontal, -1), None);
+        assert_eq!(board.make_move(5, Direction::Horizontal, 1), None);
+        assert_eq!(board.make_move(5, Direction::Vertical, 0), None);
+        assert_eq!(board.make_move(12, Direction::Vertical, 1), None);
+
+        let moves = [
+            board.make_move(5, Direction::Vertical, -1).unwrap(),
+            board.make_move(10, Direction::Horizontal, 1).unwrap(),
+        ];
+        let played = board.replay(&moves).unwrap();
+        assert_eq!(played.pieces()[5].location(), (5, 1));
+        assert_eq!(played.pieces()[10].location(), (3, 4));
+    }
+
+    #[test]
+    fn pieces_that_can_reach_a_tile() {
+        let board = Board::sample();
+        // `l` along the bottom row
+        assert_eq!(board.piece_can_reach(11, (4, 5)), Some(Move::Right(11, 1)));
+        assert_eq!(board.piece_can_reach(11, (5, 5)), Some(Move::Right(11, 2)));
+        assert_eq!(board.piece_can_reach(11, (1, 5)), None);
+        assert_eq!(board.piece_can_reach(11, (3, 5)), None);
+        assert_eq!(board.piece_can_reach(11, (4, 4)), None);
+        assert_eq!(board.piece_can_reach(11, (6, 5)), None);
+        // `f` up its column, where `b` is in the way two tiles up
+        assert_eq!(board.piece_can_reach(5, (5, 1)), Some(Move::Up(5, 1)));
+        assert_eq!(board.piece_can_reach(5, (5, 5)), Some(Move::Down(5, 1)));
+        assert_eq!(board.piece_can_reach(5, (5, 0)), None);
+        assert_eq!(board.piece_can_reach(12, (0, 0)), None);
+
+        let mov = board.piece_can_reach(11, (5, 5)).unwrap();
+        assert_eq!(board.play(&mov).piece_at((5, 5)), Some(11));
+    }
+
+    #[test]
+    fn adjacent_boards() {
+        let board = Board::sample();
+        let next = board.play(&Move::Up(5, 1));
+        assert_eq!(board.adjacent(&next), Some(Move::Up(5, 1)));
+        assert_eq!(next.adjacent(&board), Some(Move::Down(5, 1)));
+        // two moves apart, and no move at all
+        let further = next.play(&Move::Right(10, 1));
+        assert_eq!(board.adjacent(&further), None);
+        assert_eq!(board.adjacent(&board), None);
+
+        // going two tiles either way round ends in the same place
+        let pieces = vec![Piece::marked((0, 0), 2, Direction::Horizontal)];
+        let ring = Board::new(4, 1, (3, 0), pieces).with_topology(&crate::ToroidalTopology);
+        let across = ring.play(&Move::Right(0, 2));
+        assert_eq!(ring.play(&Move::Left(0, 2)), across);
+        assert_eq!(ring.adjacent(&across), None);
+        assert_eq!(ring.adjacent(&ring.play(&Move::Left(0, 1))), Some(Move::Left(0, 1)));
+    }
+
+    /// The sample board changed by hand, without keeping it in sync
+    fn desynced(change: impl FnOnce(&mut Board)) -> Board {
+        let mut board = Board::sample();
+        change(&mut board);
+        board
+    }
+
+    #[test]
+    fn check_invariants_catches_desynced_boards() {
+        assert_eq!(Board::sample().check_invariants(), Ok(()));
+        let mut board = Board::sample();
+        for mov in [Move::Up(5, 1), Move::Right(11, 2), Move::Right(10, 1)] {
+            board.play_in_place(&mov);
+            assert_eq!(board.check_invariants(), Ok(()));
+        }
+
+        // a piece moved without updating the occupied tiles or the hash
+        let moved = desynced(|board| board.pieces[5].location = (5, 1));
+        assert!(moved.check_invariants().is_err());
+        let freed = desynced(|board| board.occupied_tiles.remove((0, 2)));
+        assert!(freed.check_invariants().unwrap_err().contains("occupied tiles"));
+        let won = desynced(|board| board.is_won = true);
+        assert!(won.check_invariants().unwrap_err().contains("win flag"));
+        let hashed = desynced(|board| board.zobrist ^= 1);
+        assert!(hashed.check_invariants().unwrap_err().contains("hash"));
+        let overlapping = desynced(|board| board.pieces[3].location = (1, 2));
+        assert!(overlapping.check_invariants().unwrap_err().contains("overlaps"));
+        let off_board = desynced(|board| board.pieces[1].location = (4, 0));
+        assert!(off_board.check_invariants().unwrap_err().contains("off the board"));
+        let renumbered = desynced(|board| board.pieces[0].id = 1);
+        assert!(renumbered.check_invariants().unwrap_err().contains("id"));
+    }
+
+    #[test]
+    fn signatures_and_shapes() {
+        let board = Board::sample();
+        assert_eq!(board.signature(), (6, 6, 12));
+        let played = board.play(&Move::Up(5, 1)).play(&Move::Right(11, 2));
+        assert_eq!(played.signature(), board.signature());
+        assert_eq!(played.shape_hash(), board.shape_hash());
+        assert_eq!(board.rotate_180().shape_hash(), board.shape_hash());
+
+        // the same pieces somewhere else entirely
+        let pieces = |vertical, single| {
+            vec![
+                Piece::marked((0, 1), 2, Direction::Horizontal),
+                Piece::new((3, 0), 3, vertical),
+                Piece::new((0, 3), 1, single),
+            ]
+        };
+        let moved = vec![
+            Piece::new((0, 0), 1, Direction::Horizontal),
+            Piece::new((1, 0), 3, Direction::Vertical),
+            Piece::marked((2, 3), 2, Direction::Horizontal),
+        ];
+        let shape = |pieces| Board::new(6, 4, (5, 1), pieces).shape_hash();
+        let hash = shape(pieces(Direction::Vertical, Direction::Horizontal));
+        assert_eq!(shape(moved), hash);
+        // a single tile has no axis, but a longer piece does
+        assert_eq!(shape(pieces(Direction::Vertical, Direction::Vertical)), hash);
+        assert_ne!(shape(pieces(Direction::Horizontal, Direction::Horizontal)), hash);
+        assert_ne!(shape(pieces(Direction::Vertical, Direction::Vertical)[..2].to_vec()), hash);
+    }
+
+    #[test]
+    fn moves_limited_to_some_steps() {
+        let mut board = Board::sample();
+        for mov in [Move::Right(11, 2), Move::Up(5, 1), Move::Right(10, 2)] {
+            assert_eq!(board.all_moves_limited(1), board.all_single_moves());
+            assert_eq!(board.all_moves_limited(u32::MAX), board.all_moves());
+            let limited: Vec<Move> = board
+                .all_moves()
+                .into_iter()
+                .filter(|mov| mov.steps() <= 2)
+                .collect();
+            assert_eq!(board.all_moves_limited(2), limited);
+            assert!(board.all_moves_limited(0).is_empty());
+            board.play_in_place(&mov);
+        }
+    }
+
+    #[test]
+    fn play_in_place_and_undo_in_place() {
+        let board = Board::sample();
+        for mov in board.all_moves() {
+            let mut played = board.clone();
+            played.play_in_place(&mov);
+            assert_eq!(played, board.play(&mov));
+            assert_eq!(played.check_invariants(), Ok(()));
+            played.undo_in_place(&mov);
+            assert_eq!(played, board);
+        }
+
+        // a whole solution played forwards and taken back
+        #[cfg(feature = "std")]
+        {
+            let solution = crate::solve_path(board.clone()).unwrap();
+            let mut played = board.clone();
+            solution.iter().for_each(|mov| played.play_in_place(mov));
+            assert!(played.is_won);
+            solution.iter().rev().for_each(|mov| played.undo_in_place(mov));
+            assert_eq!(played, board);
+        }
+
+        // illegal moves leave the board as it was
+        let mut played = board.clone();
+        assert_eq!(played.try_play_in_place(&Move::Left(3, 1)), Err(MoveError::Blocked));
+        assert_eq!(played.try_undo_in_place(&Move::Up(5, 2)), Err(MoveError::Blocked));
+        assert_eq!(played, board);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::board::*;
+use crate::topology::Topology;
+use alloc::vec::Vec;
+
+/// The size used when no size is given to the builder
+const DEFAULT_SIZE: (u32, u32) = (6, 6);
+
+/// Builds a board one piece at a time, the size
+/// defaults to 6x6 but a goal has to be given
+#[derive(Debug, Clone, Default)]
+pub struct BoardBuilder {
+    size: Option<(u32, u32)>,
+    goal: Option<Goal>,
+    pieces: Vec<Piece>,
+    walls: Vec<Tile>,
+    win_mode: WinMode,
+    topology: Option<&'static dyn Topology>,
+}
+
+impl BoardBuilder {
+    /// Create a builder with no pieces
+    pub fn new() -> Self {
+        BoardBuilder::default()
+    }
+
+    /// Set the width and height of the board, defaults to 6x6
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Set the goal tile, this or an exit is required. A tile just past
+    /// the right or the bottom edge is an exit, see `Goal::Tile`.
+    pub fn goal(mut self, x: u32, y: u32) -> Self {
+        self.goal = Some(Goal::Tile((x, y)));
+        self
+    }
+
+    /// Set the goal to an exit on a side of the board,
+    /// in the given row or column, see `Goal::Edge`
+    pub fn exit(mut self, side: Side, coordinate: u32) -> Self {
+        self.goal = Some(Goal::Edge(side, coordinate));
+        self
+    }
+
+    /// Add a piece
+    pub fn piece(mut self, location: Tile, size: u32, direction: Direction) -> Self {
+        self.pieces.push(Piece::new(location, size, direction));
+        self
+    }
+
+    /// Add a frozen piece, that never moves
+    pub fn frozen_piece(mut self, location: Tile, size: u32, direction: Direction) -> Self {
+        self.pieces.push(Piece::frozen(location, size, direction));
+        self
+    }
+
+    /// Add a marked piece
+    pub fn marked_piece(mut self, location: Tile, size: u32, direction: Direction) -> Self {
+        self.pieces.push(Piece::marked(location, size, direction));
+        self
+    }
+
+    /// Add a marked piece with a goal of its own
+    pub fn marked_piece_with_goal(
+        mut self,
+        location: Tile,
+        size: u32,
+        direction: Direction,
+        goal: Tile,
+    ) -> Self {
+        self.pieces.push(Piece::marked_with_goal(location, size, direction, goal));
+        self
+    }
+
+    /// Set which tile of the marked pieces has to cover the goal,
+    /// defaults to `WinMode::AnyCell`
+    pub fn win_mode(mut self, win_mode: WinMode) -> Self {
+        self.win_mode = win_mode;
+        self
+    }
+
+    /// Set how the tiles of the board are connected,
+    /// defaults to `RectTopology`
+    pub fn topology(mut self, topology: &'static dyn Topology) -> Self {
+        self.topology = Some(topology);
+        self
+    }
+
+    /// Add a wall
+    pub fn wall(mut self, x: u32, y: u32) -> Self {
+        self.walls.push((x, y));
+        self
+    }
+
+    /// Create the board, validating it like `Board::try_with_walls`
+    pub fn build(self) -> Result<Board, BoardError> {
+        let (width, height) = self.size.unwrap_or(DEFAULT_SIZE);
+        let goal = self.goal.ok_or(BoardError::MissingGoal)?;
+        let (win_mode, topology) = (self.win_mode, self.topology);
+        let board = Board::try_with_walls(width, height, goal, self.pieces, self.walls)?;
+        let board = board.with_win_mode(win_mode);
+        Ok(match topology {
+            Some(topology) => board.with_topology(topology),
+            None => board,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn builds_the_sample_board() {
+        let board = BoardBuilder::new()
+            .goal(5, 2)
+            .marked_piece((0, 2), 2, Direction::Horizontal)
+            .piece((0, 3), 2, Direction::Horizontal)
+            .piece((0, 4), 2, Direction::Vertical)
+            .piece((1, 4), 2, Direction::Vertical)
+            .piece((2, 0), 2, Direction::Vertical)
+            .piece((2, 2), 2, Direction::Vertical)
+            .piece((2, 4), 2, Direction::Horizontal)
+            .piece((2, 5), 2, Direction::Horizontal)
+            .piece((3, 0), 3, Direction::Horizontal)
+            .piece((3, 3), 2, Direction::Horizontal)
+            .piece((3, 1), 2, Direction::Vertical)
+            .piece((5, 2), 3, Direction::Vertical)
+            .build();
+        // the size defaults to the size of the sample board
+        assert_eq!(board, Ok(Board::sample()));
+    }
+
+    #[test]
+    fn reports_what_is_missing() {
+        let marked = BoardBuilder::new().marked_piece((0, 0), 2, Direction::Horizontal);
+        assert_eq!(marked.clone().build(), Err(BoardError::MissingGoal));
+        assert_eq!(
+            BoardBuilder::new().goal(1, 0).build(),
+            Err(BoardError::MarkedPieceCount(0))
+        );
+
+        let board = marked.size(4, 1).exit(Side::Right, 0).wall(2, 0).build();
+        assert!(board.is_ok());
+        assert_eq!(board.map(|b| (b.goal, b.walls)), Ok(((3, 0), vec![(2, 0)])));
+    }
+}
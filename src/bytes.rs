@@ -0,0 +1,230 @@
+use crate::board::*;
+use alloc::vec;
+use alloc::vec::Vec;
+
+// the bits of the flags byte of a piece
+const VERTICAL: u8 = 1;
+const MARKED: u8 = 1 << 1;
+const FROZEN: u8 = 1 << 2;
+const OWN_GOAL: u8 = 1 << 3;
+
+/// The most tiles a decoded board can have, so that a few bytes can't
+/// make the board allocate more than a few kilobytes of tiles
+const MAX_TILES: u64 = 1 << 16;
+
+/// Append a number in as few bytes as it fits in, seven bits per byte
+/// with the high bit set on every byte but the last
+fn write_number(bytes: &mut Vec<u8>, mut n: u32) {
+    while n >= 0x80 {
+        bytes.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+/// The bytes of an encoded board, read from the front
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, ParseError> {
+        let byte = *self
+            .bytes
+            .get(self.offset)
+            .ok_or(ParseError::InvalidBytes(self.offset))?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    /// Read a number written by `write_number`
+    fn number(&mut self) -> Result<u32, ParseError> {
+        let start = self.offset;
+        let mut n: u32 = 0;
+        for shift in (0..32).step_by(7) {
+            let byte = self.byte()?;
+            // the fifth byte only has room for the four highest bits
+            if shift == 28 && byte > 0x0f {
+                return Err(ParseError::InvalidBytes(start));
+            }
+            n |= u32::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(ParseError::InvalidBytes(start))
+    }
+
+    fn tile(&mut self) -> Result<Tile, ParseError> {
+        Ok((self.number()?, self.number()?))
+    }
+}
+
+impl Board {
+    /// Encode the board in a compact binary format, read back with
+    /// `from_bytes`. Every number takes a byte if it is below 128, so
+    /// a 6x6 puzzle takes about four bytes per piece. The topology
+    /// isn't encoded, like when a board is serialized.
+    ///
+    /// The width, height and goal come first, then the win mode as a
+    /// byte, the number of pieces and each piece as its location, size and
+    /// a byte of flags followed by its own goal if it has one, and last
+    /// the number of walls and their tiles.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        write_number(&mut bytes, self.width);
+        write_number(&mut bytes, self.height);
+        write_number(&mut bytes, self.goal.0);
+        write_number(&mut bytes, self.goal.1);
+        bytes.push(match self.win_mode() {
+            WinMode::AnyCell => 0,
+            WinMode::Anchor => 1,
+            WinMode::FarEnd => 2,
+        });
+
+        write_number(&mut bytes, self.pieces().len() as u32);
+        for piece in self.pieces() {
+            let (x, y) = piece.location();
+            write_number(&mut bytes, x);
+            write_number(&mut bytes, y);
+            write_number(&mut bytes, piece.size());
+
+            let mut flags = 0;
+            if piece.direction() == Direction::Vertical {
+                flags |= VERTICAL;
+            }
+            if piece.is_marked() {
+                flags |= MARKED;
+            }
+            if piece.is_frozen() {
+                flags |= FROZEN;
+            }
+            if piece.goal().is_some() {
+                flags |= OWN_GOAL;
+            }
+            bytes.push(flags);
+            if let Some((x, y)) = piece.goal() {
+                write_number(&mut bytes, x);
+                write_number(&mut bytes, y);
+            }
+        }
+
+        write_number(&mut bytes, self.walls.len() as u32);
+        for &(x, y) in &self.walls {
+            write_number(&mut bytes, x);
+            write_number(&mut bytes, y);
+        }
+        bytes
+    }
+
+    /// Decode a board encoded by `to_bytes`. The board is checked like in
+    /// `try_with_walls` and can have at most 65536 tiles, so bytes from
+    /// anywhere are safe to decode.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Board, ParseError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let (width, height) = (reader.number()?, reader.number()?);
+        if u64::from(width) * u64::from(height) > MAX_TILES {
+            return Err(ParseError::InvalidBoard(BoardError::TooLarge));
+        }
+        let goal = reader.tile()?;
+        let win_mode = match reader.byte()? {
+            0 => WinMode::AnyCell,
+            1 => WinMode::Anchor,
+            2 => WinMode::FarEnd,
+            _ => return Err(ParseError::InvalidBytes(reader.offset - 1)),
+        };
+
+        // the counts aren't trusted to allocate up front
+        let mut pieces = vec![];
+        for _ in 0..reader.number()? {
+            let location = reader.tile()?;
+            let size = reader.number()?;
+            let flags = reader.byte()?;
+            if flags & !(VERTICAL | MARKED | FROZEN | OWN_GOAL) != 0 {
+                return Err(ParseError::InvalidBytes(reader.offset - 1));
+            }
+
+            let direction = if flags & VERTICAL != 0 {
+                Direction::Vertical
+            } else {
+                Direction::Horizontal
+            };
+            let piece = match (flags & MARKED != 0, flags & OWN_GOAL != 0) {
+                (true, true) => Piece::marked_with_goal(location, size, direction, reader.tile()?),
+                (true, false) => Piece::marked(location, size, direction),
+                (false, false) => Piece::new(location, size, direction),
+                // only marked pieces have goals
+                (false, true) => return Err(ParseError::InvalidBytes(reader.offset - 1)),
+            };
+            pieces.push(piece.with_frozen(flags & FROZEN != 0));
+        }
+
+        let mut walls = vec![];
+        for _ in 0..reader.number()? {
+            walls.push(reader.tile()?);
+        }
+        if reader.offset != bytes.len() {
+            return Err(ParseError::InvalidBytes(reader.offset));
+        }
+
+        Board::try_with_walls(width, height, goal, pieces, walls)
+            .map(|board| board.with_win_mode(win_mode))
+            .map_err(ParseError::InvalidBoard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_sample() {
+        let board = Board::sample();
+        assert_eq!(Board::from_bytes(&board.to_bytes()).unwrap(), board);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn round_trip_generated() {
+        for seed in 0..20 {
+            let board = crate::generate_seeded(6, 6, 8, seed);
+            assert_eq!(Board::from_bytes(&board.to_bytes()).unwrap(), board);
+        }
+    }
+
+    #[test]
+    fn round_trip_walls_and_goals() {
+        let pieces = vec![
+            Piece::marked_with_goal((0, 0), 2, Direction::Horizontal, (4, 0)),
+            Piece::marked((0, 2), 2, Direction::Horizontal),
+            Piece::new((3, 1), 2, Direction::Vertical).with_frozen(true),
+        ];
+        let board = Board::try_with_walls(5, 4, (4, 2), pieces, vec![(2, 3)])
+            .unwrap()
+            .with_win_mode(WinMode::FarEnd);
+        assert_eq!(Board::from_bytes(&board.to_bytes()).unwrap(), board);
+    }
+
+    #[test]
+    fn huge_board_is_rejected() {
+        // a width of u32::MAX, a height of one and a piece as wide as the board
+        let mut bytes = vec![0xff, 0xff, 0xff, 0xff, 0x0f, 1, 0, 0, 0, 1, 0, 0];
+        bytes.extend([0xff, 0xff, 0xff, 0xff, 0x0f, MARKED, 0]);
+        assert!(matches!(
+            Board::from_bytes(&bytes),
+            Err(ParseError::InvalidBoard(BoardError::TooLarge))
+        ));
+    }
+
+    #[test]
+    fn truncated_bytes_are_rejected() {
+        let bytes = Board::sample().to_bytes();
+        for end in 0..bytes.len() {
+            assert!(Board::from_bytes(&bytes[..end]).is_err());
+        }
+        let mut extra = bytes;
+        extra.push(0);
+        assert!(matches!(Board::from_bytes(&extra), Err(ParseError::InvalidBytes(_))));
+    }
+}
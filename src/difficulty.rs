@@ -0,0 +1,400 @@
+use crate::board::*;
+use crate::solver::{solve, solve_path, BoardHasher, Visited};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Solutions of at most this many moves are trivial
+const TRIVIAL_MOVES: u32 = 3;
+/// Solutions of at most this many moves are easy
+const EASY_MOVES: u32 = 10;
+/// Solutions of at most this many moves are of medium difficulty
+const MEDIUM_MOVES: u32 = 25;
+/// Boards with more legal moves than this on average along the
+/// solution are rated one level harder than their length suggests
+const HIGH_BRANCHING: f64 = 15.0;
+
+/// How hard a board is to solve
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, PartialOrd, Ord)]
+pub enum DifficultyRating {
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl DifficultyRating {
+    /// Rate a board by the length of its optimal solution, a board with
+    /// many moves to choose from along the way is rated one level harder.
+    /// Returns `None` if the board is unsolvable.
+    pub fn of(board: &Board) -> Option<Self> {
+        let moves = solve_path(board.clone())?;
+        let steps = moves.len() as u32;
+
+        let rating = match steps {
+            s if s <= TRIVIAL_MOVES => return Some(DifficultyRating::Trivial),
+            s if s <= EASY_MOVES => DifficultyRating::Easy,
+            s if s <= MEDIUM_MOVES => DifficultyRating::Medium,
+            _ => return Some(DifficultyRating::Hard),
+        };
+
+        // the average number of legal moves on the boards along the solution
+        let branching = average_moves(path(board, &moves));
+
+        match rating {
+            DifficultyRating::Easy if branching > HIGH_BRANCHING => Some(DifficultyRating::Medium),
+            DifficultyRating::Medium if branching > HIGH_BRANCHING => Some(DifficultyRating::Hard),
+            rating => Some(rating),
+        }
+    }
+}
+
+/// Which boards `Board::branching_factor` averages over
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum BranchingScope {
+    /// Every board that can be reached, the board itself included
+    Reachable,
+    /// The boards along an optimal solution, before each of its moves
+    SolutionPath,
+}
+
+impl Board {
+    /// The average number of legal moves on the boards in the scope. It is
+    /// `None` for the solution path if the board can't be solved, and `0`
+    /// for the solution path of a board that is already won, which has no
+    /// moves along it.
+    pub fn branching_factor(&self, scope: BranchingScope) -> Option<f64> {
+        match scope {
+            BranchingScope::Reachable => Some(average_moves(self.clone().reachable())),
+            BranchingScope::SolutionPath => {
+                let moves = solve_path(self.clone())?;
+                Some(average_moves(path(self, &moves)))
+            }
+        }
+    }
+
+    /// Map every piece to the pieces right next to it at either end, along
+    /// the axis it moves in, which keep it from moving that way. Walls and
+    /// the edges of the board block pieces too, but they are left out.
+    pub fn blocking_graph(&self) -> HashMap<usize, Vec<usize>> {
+        self.pieces()
+            .iter()
+            .map(|piece| {
+                let (start, end) = self.piece_ends(piece);
+                let mut neighbours = vec![];
+                if piece.moves_along(Direction::Horizontal) {
+                    neighbours.extend(self.step(start, Side::Left, 1));
+                    neighbours.extend(self.step(end, Side::Right, 1));
+                }
+                if piece.moves_along(Direction::Vertical) {
+                    neighbours.extend(self.step(start, Side::Top, 1));
+                    neighbours.extend(self.step(end, Side::Bottom, 1));
+                }
+
+                let mut blocking: Vec<usize> =
+                    neighbours.into_iter().filter_map(|t| self.piece_at(t)).collect();
+                blocking.sort_unstable();
+                blocking.dedup();
+                (piece.id(), blocking)
+            })
+            .collect()
+    }
+
+    /// Find the board among the ones reachable from this one that takes the
+    /// most moves to win, and that number of moves. Every move can be taken
+    /// back, so this is a breadth first search backwards from all the won
+    /// boards that can be reached. It is `None` if there are none.
+    pub fn hardest_position(&self) -> Option<(Board, u32)> {
+        let mut queue: VecDeque<Board> = self.clone().reachable().filter(|b| b.is_won).collect();
+        let mut distances: HashMap<Board, u32, BoardHasher> = HashMap::default();
+        distances.extend(queue.iter().map(|board| (board.clone(), 0)));
+
+        // the boards leave the queue in order of their distance, so the
+        // last one is the farthest from the won boards
+        let mut hardest = None;
+        while let Some(board) = queue.pop_front() {
+            let distance = distances[&board];
+            for (future, _) in board.future_boards() {
+                if !distances.contains_key(&future) {
+                    distances.insert(future.clone(), distance + 1);
+                    queue.push_back(future);
+                }
+            }
+            hardest = Some((board, distance));
+        }
+        hardest
+    }
+}
+
+/// The boards that the moves are played on, starting with the start
+fn path<'a>(start: &Board, moves: &'a [Move]) -> impl Iterator<Item = Board> + 'a {
+    moves.iter().scan(start.clone(), |board, mov| {
+        let next = board.play(mov);
+        Some(std::mem::replace(board, next))
+    })
+}
+
+/// The average number of legal moves on the boards, `0` if there are none
+fn average_moves(boards: impl Iterator<Item = Board>) -> f64 {
+    let (count, total) = boards.fold((0, 0), |(count, total), board| {
+        (count + 1, total + board.all_moves().len())
+    });
+    if count == 0 {
+        0.0
+    } else {
+        total as f64 / count as f64
+    }
+}
+
+/// The number of moves in an optimal solution of the board,
+/// `0` if it is already won and `None` if it can't be solved
+pub fn difficulty(board: &Board) -> Option<u32> {
+    solve(board.clone(), &mut Visited::default()).map(|(_, steps)| steps)
+}
+
+/// Count the number of distinct shortest move sequences that win the
+/// board, `1` if it is already won and `0` if it can't be solved.
+/// The count saturates at `usize::MAX`.
+pub fn count_optimal_solutions(board: &Board) -> usize {
+    if board.is_won {
+        return 1;
+    }
+
+    // every board of the earlier waves
+    let mut seen: HashSet<Board, BoardHasher> = HashSet::default();
+    seen.insert(board.clone());
+    // the boards of the current wave and the number of shortest paths to them
+    let mut paths: HashMap<Board, usize, BoardHasher> = HashMap::default();
+    paths.insert(board.clone(), 1);
+
+    while !paths.is_empty() {
+        let mut next_paths: HashMap<Board, usize, BoardHasher> = HashMap::default();
+        for (board, count) in &paths {
+            for (future, _) in board.future_boards() {
+                if !seen.contains(&future) {
+                    let entry = next_paths.entry(future).or_insert(0);
+                    *entry = entry.saturating_add(*count);
+                }
+            }
+        }
+
+        let solutions = next_paths
+            .iter()
+            .filter(|(board, _)| board.is_won)
+            .fold(0, |total: usize, (_, count)| total.saturating_add(*count));
+        if solutions > 0 {
+            return solutions;
+        }
+
+        seen.extend(next_paths.keys().cloned());
+        paths = next_paths;
+    }
+
+    0
+}
+
+/// Check if there is exactly one shortest move sequence that wins the board
+pub fn has_unique_solution(board: &Board) -> bool {
+    count_optimal_solutions(board) == 1
+}
+
+/// Count how many times each piece of the start board is moved by the
+/// moves, by its id. A move counts once however many steps it takes, and
+/// pieces that never move count zero times. The moves are replayed from
+/// the start board first, returning the first one that can't be played.
+pub fn piece_move_counts(
+    start: &Board,
+    moves: &[Move],
+) -> Result<HashMap<usize, u32>, ReplayError> {
+    start.replay(moves)?;
+    let mut counts: HashMap<usize, u32> = start.pieces().iter().map(|p| (p.id(), 0)).collect();
+    for mov in moves {
+        *counts.entry(mov.get_piece()).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_counts_of_the_sample_solution() {
+        let board = Board::sample();
+        let solution = solve_path(board.clone()).unwrap();
+        let counts = piece_move_counts(&board, &solution).unwrap();
+        assert_eq!(counts.len(), board.pieces().len());
+        assert_eq!(counts.values().sum::<u32>(), 50);
+        assert_eq!(counts[&3], 3);
+    }
+
+    #[test]
+    fn slides_count_once() {
+        let board = Board::sample();
+        let counts = piece_move_counts(&board, &[Move::Right(11, 2), Move::Up(5, 1)]).unwrap();
+        assert_eq!((counts[&11], counts[&5], counts[&3]), (1, 1, 0));
+    }
+
+    #[test]
+    fn illegal_moves_are_reported() {
+        let board = Board::sample();
+        let error = piece_move_counts(&board, &[Move::Up(5, 1), Move::Right(3, 1)]).unwrap_err();
+        assert_eq!((error.index, error.mov), (1, Move::Right(3, 1)));
+    }
+
+    #[test]
+    fn count_solutions() {
+        let single = |location| vec![Piece::marked(location, 1, Direction::Horizontal)];
+        // a single slide to the goal
+        let unique = Board::new(4, 1, (3, 0), single((0, 0)));
+        assert_eq!(count_optimal_solutions(&unique), 1);
+        assert!(has_unique_solution(&unique));
+
+        // right then down or down then right
+        let corner = Board::new(3, 3, (2, 2), single((0, 1)));
+        assert_eq!(count_optimal_solutions(&corner), 2);
+        assert!(!has_unique_solution(&corner));
+
+        let won = Board::new(3, 1, (0, 0), single((0, 0)));
+        assert_eq!(count_optimal_solutions(&won), 1);
+        let stuck = vec![
+            Piece::marked((0, 0), 2, Direction::Horizontal),
+            Piece::new((2, 0), 2, Direction::Vertical),
+        ];
+        assert_eq!(count_optimal_solutions(&Board::new(3, 2, (2, 0), stuck)), 0);
+    }
+
+    #[test]
+    fn blocking_graph_of_the_sample() {
+        let graph = Board::sample().blocking_graph();
+        let expected: [&[usize]; 12] = [
+            &[4],
+            &[0],
+            &[1, 7],
+            &[4],
+            &[0, 10],
+            &[],
+            &[4],
+            &[4, 5],
+            &[6],
+            &[6],
+            &[9],
+            &[9],
+        ];
+        assert_eq!(graph.len(), expected.len());
+        for (id, blocking) in expected.iter().enumerate() {
+            assert_eq!(graph[&id], *blocking, "piece {}", id);
+        }
+        // `a` and `e` block each other, and the edge that blocks `b` on
+        // the right is left out
+        assert!(graph[&0].contains(&4) && graph[&4].contains(&0));
+    }
+
+    #[test]
+    fn hardest_position_of_the_sample() {
+        let board = Board::sample();
+        let (hardest, distance) = board.hardest_position().unwrap();
+        assert!(distance >= 50);
+        assert_eq!(solve_path(hardest.clone()).map(|s| s.len() as u32), Some(distance));
+        assert!(board.clone().reachable().any(|b| b == hardest));
+
+        let pieces = vec![
+            Piece::marked((0, 0), 2, Direction::Horizontal),
+            Piece::new((2, 0), 1, Direction::Vertical).with_frozen(true),
+        ];
+        assert_eq!(Board::new(4, 1, (3, 0), pieces).hardest_position(), None);
+    }
+
+    /// A board that `quick_unsolvable` doesn't rule out, where the marked
+    /// piece is stuck behind a column it fills
+    fn stuck_board() -> Board {
+        let pieces = vec![
+            Piece::marked((0, 1), 2, Direction::Horizontal),
+            Piece::new((1, 0), 1, Direction::Horizontal),
+            Piece::new((3, 0), 3, Direction::Vertical),
+        ];
+        Board::new(4, 3, (3, 1), pieces)
+    }
+
+    #[test]
+    fn difficulty_of_won_and_unsolvable_boards() {
+        let board = Board::sample();
+        assert_eq!(difficulty(&board), Some(50));
+        let won = board.replay(&solve_path(board.clone()).unwrap()).unwrap();
+        assert_eq!(difficulty(&won), Some(0));
+        assert_eq!(DifficultyRating::of(&won), Some(DifficultyRating::Trivial));
+
+        // the search runs out of boards rather than looping
+        assert!(!stuck_board().quick_unsolvable());
+        assert_eq!(difficulty(&stuck_board()), None);
+        assert_eq!(DifficultyRating::of(&stuck_board()), None);
+    }
+
+    #[test]
+    fn ratings_by_solution_length() {
+        // the boards along the solution of the sample are a move easier
+        // each, and only have a few legal moves
+        let board = Board::sample();
+        let solution = solve_path(board.clone()).unwrap();
+        let rating = |moves_left: usize| {
+            let played = board.replay(&solution[..50 - moves_left]).unwrap();
+            assert_eq!(difficulty(&played), Some(moves_left as u32));
+            let branching = played.branching_factor(BranchingScope::SolutionPath);
+            assert!(branching.unwrap() < HIGH_BRANCHING);
+            DifficultyRating::of(&played).unwrap()
+        };
+        assert_eq!(rating(1), DifficultyRating::Trivial);
+        assert_eq!(rating(3), DifficultyRating::Trivial);
+        assert_eq!(rating(4), DifficultyRating::Easy);
+        assert_eq!(rating(10), DifficultyRating::Easy);
+        assert_eq!(rating(11), DifficultyRating::Medium);
+        assert_eq!(rating(25), DifficultyRating::Medium);
+        assert_eq!(rating(26), DifficultyRating::Hard);
+        assert_eq!(rating(50), DifficultyRating::Hard);
+    }
+
+    #[test]
+    fn many_moves_to_choose_from_make_a_board_harder() {
+        // four moves, `e`, `d` and `b` and then the marked piece, on a
+        // board where every piece can slide far
+        let grid = "\
+            XX.b....\n\
+            ..cb....\n\
+            ..cdde..\n\
+            .....e..\n\
+            ........\n\
+            ........\n\
+            ........\n\
+            ........";
+        let board = Board::from_ascii(grid, (7, 0)).unwrap();
+        assert_eq!(difficulty(&board), Some(4));
+        let branching = board.branching_factor(BranchingScope::SolutionPath);
+        assert!(branching.unwrap() > HIGH_BRANCHING);
+        assert_eq!(DifficultyRating::of(&board), Some(DifficultyRating::Medium));
+    }
+
+    #[test]
+    fn branching_of_a_single_tile() {
+        // the tile has two moves wherever it is on the three tiles
+        let tile = |x| {
+            let pieces = vec![Piece::marked((x, 0), 1, Direction::Horizontal)];
+            Board::new(3, 1, (2, 0), pieces)
+        };
+        let start = tile(0);
+        assert_eq!(start.branching_factor(BranchingScope::Reachable), Some(2.0));
+        assert_eq!(start.branching_factor(BranchingScope::SolutionPath), Some(2.0));
+
+        // a won board still reaches the others, but its solution has no moves
+        let won = tile(2);
+        assert!(won.is_won);
+        assert_eq!(won.branching_factor(BranchingScope::Reachable), Some(2.0));
+        assert_eq!(won.branching_factor(BranchingScope::SolutionPath), Some(0.0));
+
+        // the tile can't move past the frozen one, nor get to the goal
+        let pieces = vec![
+            Piece::marked((0, 0), 1, Direction::Horizontal),
+            Piece::frozen((1, 0), 1, Direction::Horizontal),
+        ];
+        let stuck = Board::new(3, 1, (2, 0), pieces);
+        assert_eq!(stuck.branching_factor(BranchingScope::Reachable), Some(0.0));
+        assert_eq!(stuck.branching_factor(BranchingScope::SolutionPath), None);
+    }
+}
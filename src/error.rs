@@ -0,0 +1,184 @@
+use crate::board::*;
+#[cfg(feature = "std")]
+use crate::file::LoadError;
+#[cfg(feature = "std")]
+use crate::solver::SolveError;
+use core::fmt;
+
+/// Any error of the crate, for callers that handle them all the same way.
+/// Every fallible function returns its own error type, which converts
+/// into this one with `?`.
+#[derive(Debug)]
+pub enum Error {
+    /// A board couldn't be parsed from text
+    Parse(ParseError),
+    /// A board couldn't be created from its pieces
+    Board(BoardError),
+    /// A move couldn't be played
+    Move(MoveError),
+    /// A move in a list of moves couldn't be played
+    Replay(ReplayError),
+    /// A solver didn't return a solution
+    #[cfg(feature = "std")]
+    Solve(SolveError),
+    /// A board couldn't be loaded from a file
+    #[cfg(feature = "std")]
+    Load(LoadError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "invalid board: {}", e),
+            Error::Board(e) => write!(f, "invalid board: {}", e),
+            Error::Move(e) => write!(f, "illegal move: {}", e),
+            Error::Replay(e) => e.fmt(f),
+            #[cfg(feature = "std")]
+            Error::Solve(e) => e.fmt(f),
+            #[cfg(feature = "std")]
+            Error::Load(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(e) => Some(e),
+            Error::Board(e) => Some(e),
+            Error::Move(e) => Some(e),
+            Error::Replay(e) => Some(e),
+            Error::Solve(e) => Some(e),
+            Error::Load(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<BoardError> for Error {
+    fn from(e: BoardError) -> Self {
+        Error::Board(e)
+    }
+}
+
+impl From<MoveError> for Error {
+    fn from(e: MoveError) -> Self {
+        Error::Move(e)
+    }
+}
+
+impl From<ReplayError> for Error {
+    fn from(e: ReplayError) -> Self {
+        Error::Replay(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<SolveError> for Error {
+    fn from(e: SolveError) -> Self {
+        Error::Solve(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<LoadError> for Error {
+    fn from(e: LoadError) -> Self {
+        Error::Load(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn marked() -> Piece {
+        Piece::marked((0, 0), 2, Direction::Horizontal)
+    }
+
+    #[test]
+    fn malformed_boards_are_errors() {
+        let board = |pieces: Vec<Piece>| Board::try_new(4, 3, (3, 0), pieces);
+        let empty = Piece::new((0, 2), 0, Direction::Horizontal);
+        assert_eq!(board(vec![marked(), empty]), Err(BoardError::EmptyPiece(1)));
+        let long = Piece::new((1, 1), 4, Direction::Horizontal);
+        assert_eq!(board(vec![marked(), long]), Err(BoardError::OutOfBounds(1)));
+        let far = Piece::new((u32::MAX, 1), 2, Direction::Horizontal);
+        assert_eq!(board(vec![marked(), far]), Err(BoardError::OutOfBounds(1)));
+        let overlapping = Piece::new((1, 0), 2, Direction::Vertical);
+        assert_eq!(board(vec![marked(), overlapping]), Err(BoardError::Overlap(1)));
+        assert_eq!(board(vec![]), Err(BoardError::MarkedPieceCount(0)));
+        assert_eq!(
+            Board::try_new(4, 3, (4, 4), vec![marked()]),
+            Err(BoardError::GoalOutOfBounds)
+        );
+        assert_eq!(
+            Board::try_with_walls(4, 3, (3, 0), vec![marked()], vec![(0, 3)]),
+            Err(BoardError::WallOutOfBounds(0))
+        );
+        assert_eq!(
+            Board::try_new(u32::MAX, u32::MAX, (0, 0), vec![marked()]),
+            Err(BoardError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn malformed_drawings_are_errors() {
+        assert_eq!(Board::from_ascii("", (0, 0)), Err(ParseError::Empty));
+        assert_eq!(
+            Board::from_ascii("XX.\n..", (2, 0)),
+            Err(ParseError::RaggedRow { row: 1, expected: 3, found: 2 })
+        );
+        assert_eq!(
+            Board::from_ascii("XX?", (2, 0)),
+            Err(ParseError::UnexpectedChar('?', (2, 0)))
+        );
+        assert_eq!(
+            Board::from_ascii("XXa\n.aa", (2, 0)),
+            Err(ParseError::BrokenPiece('a'))
+        );
+        assert_eq!(Board::from_ascii("aa.", (2, 0)), Err(ParseError::NoMarkedPiece));
+        assert!(Board::from_bytes(&[0xff; 8]).is_err());
+    }
+
+    #[test]
+    fn illegal_moves_are_errors() {
+        let board = Board::sample();
+        assert_eq!(board.try_play(&Move::Up(12, 1)), Err(MoveError::UnknownPiece(12)));
+        assert_eq!(board.try_play(&Move::Up(3, 1)), Err(MoveError::WrongDirection));
+        assert_eq!(board.try_play(&Move::Up(5, 0)), Err(MoveError::NoSteps));
+        assert_eq!(board.try_play(&Move::Up(5, u32::MAX)), Err(MoveError::Blocked));
+        assert_eq!(board.try_undo(&Move::Down(5, 3)), Err(MoveError::Blocked));
+        assert_eq!(Move::Up(12, 1).try_cost(&board), Err(MoveError::UnknownPiece(12)));
+        assert_eq!(Move::Up(5, u32::MAX).try_cost(&board), Ok(u32::MAX));
+    }
+
+    #[test]
+    fn errors_convert_with_the_question_mark() {
+        fn play(drawing: &str, mov: Move) -> Result<Board, Error> {
+            let board = Board::from_ascii(drawing, (3, 0))?;
+            Ok(board.try_play(&mov)?)
+        }
+        assert!(play("XX..", Move::Right(0, 2)).unwrap().is_won);
+
+        let error = play("XX.?", Move::Right(0, 2)).unwrap_err();
+        assert!(matches!(error, Error::Parse(ParseError::UnexpectedChar('?', (3, 0)))));
+        assert_eq!(error.to_string(), "invalid board: unexpected character '?' at (3,0)");
+        let error = play("XX..", Move::Right(0, 3)).unwrap_err();
+        assert!(matches!(error, Error::Move(MoveError::Blocked)));
+        assert_eq!(error.to_string(), "illegal move: the path of the piece is blocked");
+
+        let error = Error::from(Board::sample().replay(&[Move::Up(3, 1)]).unwrap_err());
+        assert!(matches!(error, Error::Replay(ReplayError { index: 0, .. })));
+        let error = Error::from(BoardError::MissingGoal);
+        assert_eq!(error.to_string(), "invalid board: the board has no goal");
+    }
+}
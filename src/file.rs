@@ -0,0 +1,270 @@
+use crate::board::*;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Reasons that a board couldn't be loaded from a file
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file couldn't be read
+    Io(io::Error),
+    /// The file isn't a valid ASCII grid
+    Parse(ParseError),
+    /// The file isn't a valid serialized board
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "could not read the file: {}", e),
+            LoadError::Parse(e) => write!(f, "invalid board: {}", e),
+            #[cfg(feature = "serde")]
+            LoadError::Json(e) => write!(f, "invalid JSON board: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Parse(e) => Some(e),
+            #[cfg(feature = "serde")]
+            LoadError::Json(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl From<ParseError> for LoadError {
+    fn from(e: ParseError) -> Self {
+        LoadError::Parse(e)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for LoadError {
+    fn from(e: serde_json::Error) -> Self {
+        LoadError::Json(e)
+    }
+}
+
+impl Board {
+    /// Load a board from a file. With the `serde` feature files ending in
+    /// `.json` are read as a serialized board, other files are read as an
+    /// ASCII grid like `Display` draws it, where `+` is the goal, or a gap
+    /// in the wall if it is past the end of a row. Without a `+` the goal
+    /// is the exit on the right edge of the row of the marked piece, like
+    /// in Rush Hour.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Board, LoadError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        #[cfg(feature = "serde")]
+        {
+            if is_json(path) {
+                return Ok(serde_json::from_str(&text)?);
+            }
+        }
+        Ok(Board::from_drawing(&text)?)
+    }
+
+    /// Write the board to a file, as JSON if the path ends in `.json` and
+    /// the `serde` feature is enabled and as the ASCII grid of `Display`
+    /// otherwise. The grid leaves out goals that are covered, the goals of
+    /// marked pieces and the win mode, which JSON keeps.
+    pub fn to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        #[cfg(feature = "serde")]
+        {
+            if is_json(path) {
+                return fs::write(path, serde_json::to_string(self)?);
+            }
+        }
+        fs::write(path, format!("{}\n", self))
+    }
+
+    /// Parse several boards drawn like in `from_file`, separated by blank
+    /// lines. Each board may start with a line such as `goal: 5,2` giving
+    /// its goal tile, otherwise the goal is found like `from_file` does.
+    /// Errors are wrapped in `ParseError::InPuzzle` with the index of the
+    /// board they are in.
+    pub fn parse_many(text: &str) -> Result<Vec<Board>, ParseError> {
+        let mut blocks: Vec<Vec<&str>> = vec![];
+        let mut block = vec![];
+        for line in text.lines() {
+            if !line.trim().is_empty() {
+                block.push(line);
+            } else if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        }
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(index, lines)| {
+                Board::from_block(lines).map_err(|error| ParseError::InPuzzle {
+                    index,
+                    error: Box::new(error),
+                })
+            })
+            .collect()
+    }
+
+    /// Parse the lines of a single board for `parse_many`
+    fn from_block(lines: &[&str]) -> Result<Board, ParseError> {
+        let header = lines.first().and_then(|line| line.trim().strip_prefix("goal:"));
+        let header = match header {
+            Some(header) => header,
+            None => return Board::from_drawing(&lines.join("\n")),
+        };
+
+        let invalid = || ParseError::InvalidGoal(lines[0].trim().to_owned());
+        let (x, y) = header.split_once(',').ok_or_else(invalid)?;
+        let goal = match (x.trim().parse(), y.trim().parse()) {
+            (Ok(x), Ok(y)) => (x, y),
+            _ => return Err(invalid()),
+        };
+        Board::from_ascii(&lines[1..].join("\n").replace('+', "."), goal)
+    }
+
+    /// Parse a grid drawn like `Display` does, like `from_file` parses the
+    /// files that aren't JSON, for boards that aren't read from a file
+    pub fn from_drawing(text: &str) -> Result<Board, ParseError> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let goal = rows.iter().zip(0..).find_map(|(row, y)| {
+            let x = row.chars().position(|c| c == '+')?;
+            Some(Goal::Tile((x as u32, y)))
+        });
+        let goal = match goal {
+            Some(goal) => goal,
+            None => {
+                let row = rows
+                    .iter()
+                    .position(|row| row.contains('X'))
+                    .ok_or(ParseError::NoMarkedPiece)?;
+                Goal::Edge(Side::Right, row as u32)
+            }
+        };
+
+        // a `+` past the end of a row is a gap in the wall on the right,
+        // that row is a tile longer than the others and the gap isn't a tile
+        let width = rows.iter().map(|row| row.chars().count()).min().unwrap_or(0);
+        let grid: Vec<&str> = rows
+            .iter()
+            .map(|row| match row.strip_suffix('+') {
+                Some(rest) if rest.chars().count() == width => rest,
+                _ => row,
+            })
+            .collect();
+        Board::from_ascii(&grid.join("\n").replace('+', "."), goal)
+    }
+}
+
+/// Check if a file is named like a JSON file
+#[cfg(feature = "serde")]
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A path in the temporary directory that no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("blocked-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn round_trip_ascii_file() {
+        let path = temp_path("sample.txt");
+        Board::sample().to_file(&path).unwrap();
+        let board = Board::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(board.unwrap(), Board::sample());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn round_trip_json_file() {
+        let pieces = vec![
+            Piece::marked_with_goal((0, 0), 2, Direction::Horizontal, (4, 0)),
+            Piece::marked((0, 2), 2, Direction::Horizontal),
+        ];
+        let board = Board::try_with_walls(5, 4, (4, 2), pieces, vec![(2, 3)])
+            .unwrap()
+            .with_win_mode(WinMode::FarEnd);
+        let path = temp_path("board.json");
+        board.to_file(&path).unwrap();
+        let read = Board::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), board);
+    }
+
+    #[test]
+    fn load_errors() {
+        let missing = Board::from_file(temp_path("missing.txt"));
+        assert!(matches!(missing, Err(LoadError::Io(_))));
+
+        let path = temp_path("broken.txt");
+        fs::write(&path, "XX.\naba\n").unwrap();
+        let broken = Board::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(broken, Err(LoadError::Parse(ParseError::BrokenPiece('a')))));
+    }
+
+    #[test]
+    fn drawings() {
+        // without a `+` the goal is the exit of the row of the marked piece
+        let board = Board::from_drawing("a...\naXX.\n....").unwrap();
+        assert_eq!(board.goal, (3, 1));
+        let board = Board::from_drawing("a.+.\naXX.\n....").unwrap();
+        assert_eq!(board.goal, (2, 0));
+        // a `+` past the end of a row is a gap in the wall
+        let board = Board::from_drawing("a...\naXX.+\n....").unwrap();
+        assert_eq!((board.width, board.goal), (4, (3, 1)));
+        assert_eq!(Board::from_drawing("aa..\n...."), Err(ParseError::NoMarkedPiece));
+    }
+
+    #[test]
+    fn parse_a_level_pack() {
+        let text = format!("goal: 5,2\n{}\n\n\na.+.\naXX.\n....\n", Board::sample());
+        let boards = Board::parse_many(&text).unwrap();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0], Board::sample());
+        assert_eq!((boards[1].width, boards[1].height, boards[1].goal), (4, 3, (2, 0)));
+        assert!(boards.iter().all(|board| board.check_invariants().is_ok()));
+        assert_eq!(Board::parse_many("\n\n"), Ok(vec![]));
+    }
+
+    #[test]
+    fn pack_errors_name_the_puzzle() {
+        let in_puzzle = |index, error| ParseError::InPuzzle {
+            index,
+            error: Box::new(error),
+        };
+        let text = "XX.\n\ngoal: 2\nXX.";
+        let error = ParseError::InvalidGoal("goal: 2".to_string());
+        assert_eq!(Board::parse_many(text), Err(in_puzzle(1, error)));
+        let text = "XX.\n\naa.\n\nXX.";
+        assert_eq!(Board::parse_many(text), Err(in_puzzle(1, ParseError::NoMarkedPiece)));
+    }
+}
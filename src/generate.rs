@@ -0,0 +1,171 @@
+use crate::board::*;
+use crate::solver::{solve, Visited};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// How many times to try placing a piece before starting over
+const PLACEMENT_ATTEMPTS: u32 = 100;
+
+/// Generate a random board that is guaranteed to be solvable.
+/// The marked piece is placed in a random row with the goal on the
+/// right edge of that row, the other pieces get random positions,
+/// sizes and directions. Boards that can't be solved, or are already
+/// won, are thrown away and generated again.
+/// Panics if the pieces can't fit on a board of the given size, or if
+/// the board has more tiles than fit in a `u32`.
+pub fn generate(width: u32, height: u32, piece_count: u32, rng: &mut impl Rng) -> Board {
+    assert!(width >= 3, "the board must be at least 3 tiles wide");
+    assert!(height >= 1, "the board must be at least 1 tile high");
+    assert!(piece_count >= 1, "the board must have a marked piece");
+    // in u64 so that large sizes and counts can't overflow
+    let tiles = u64::from(width) * u64::from(height);
+    assert!(
+        tiles <= u64::from(u32::MAX),
+        "a {}x{} board has too many tiles",
+        width,
+        height
+    );
+    assert!(
+        u64::from(piece_count) * 2 < tiles,
+        "{} pieces don't fit on a {}x{} board",
+        piece_count,
+        width,
+        height
+    );
+
+    loop {
+        if let Some(board) = random_board(width, height, piece_count, rng) {
+            if !board.is_won && solve(board.clone(), &mut Visited::default()).is_some() {
+                return board;
+            }
+        }
+    }
+}
+
+/// Generate a random board like `generate`, with a random number generator
+/// made from the seed, so that the same seed always gives the same board.
+/// Boards may change between versions of the crate or of rand.
+pub fn generate_seeded(width: u32, height: u32, piece_count: u32, seed: u64) -> Board {
+    generate(width, height, piece_count, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Scramble a won board by playing random legal moves. Every move can be
+/// undone, so the scrambled board can be solved in at most `moves` moves.
+/// Once the board isn't won any more, moves that would win it again are
+/// never played, and the scramble stops early if every move would.
+pub fn scramble(solved: Board, moves: u32, rng: &mut impl Rng) -> Board {
+    let mut board = solved;
+    for _ in 0..moves {
+        let mut futures: Vec<Board> = board
+            .future_boards()
+            .into_iter()
+            .map(|(future, _)| future)
+            .filter(|future| board.is_won || !future.is_won)
+            .collect();
+        if futures.is_empty() {
+            break;
+        }
+        board = futures.swap_remove(rng.gen_range(0..futures.len()));
+    }
+    board
+}
+
+/// Place the pieces randomly without overlaps, or give up
+/// if a piece doesn't fit anywhere it was tried
+fn random_board(width: u32, height: u32, piece_count: u32, rng: &mut impl Rng) -> Option<Board> {
+    let row = rng.gen_range(0..height);
+    let goal = (width - 1, row);
+    // leave the goal free so that the board isn't already won
+    let marked = Piece::marked((rng.gen_range(0..width - 2), row), 2, Direction::Horizontal);
+
+    let mut pieces = vec![marked];
+    let mut occupied = TileSet::new(width, height);
+    pieces[0].occupies().into_iter().for_each(|t| occupied.insert(t));
+
+    for _ in 1..piece_count {
+        let piece = (0..PLACEMENT_ATTEMPTS)
+            .filter_map(|_| random_piece(width, height, rng))
+            .find(|p| p.occupies().iter().all(|t| !occupied.contains(t)))?;
+
+        piece.occupies().into_iter().for_each(|t| occupied.insert(t));
+        pieces.push(piece);
+    }
+
+    Board::try_new(width, height, goal, pieces).ok()
+}
+
+/// A piece of size 2 or 3 placed somewhere on the board,
+/// or `None` if it is too long for the board
+fn random_piece(width: u32, height: u32, rng: &mut impl Rng) -> Option<Piece> {
+    let size = rng.gen_range(2..=3);
+    let (direction, span_x, span_y) = if rng.gen() {
+        (Direction::Horizontal, size, 1)
+    } else {
+        (Direction::Vertical, 1, size)
+    };
+
+    if span_x > width || span_y > height {
+        return None;
+    }
+
+    let x = rng.gen_range(0..=width - span_x);
+    let y = rng.gen_range(0..=height - span_y);
+    Some(Piece::new((x, y), size, direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrambled_boards_are_solvable() {
+        let start = Board::sample();
+        let solved = start.replay(&crate::solve_path(start.clone()).unwrap()).unwrap();
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let scrambled = scramble(solved.clone(), 12, &mut rng);
+            assert!(!scrambled.is_won);
+            let solution = crate::solve_path(scrambled).unwrap();
+            assert!(!solution.is_empty() && solution.len() <= 12);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_board() {
+        for seed in 0..5 {
+            let board = generate_seeded(6, 6, 8, seed);
+            assert_eq!(generate_seeded(6, 6, 8, seed), board);
+            assert_eq!(generate(6, 6, 8, &mut StdRng::seed_from_u64(seed)), board);
+            assert_eq!(board.pieces().len(), 8);
+            assert!(!board.is_won);
+            assert!(crate::solve_path(board).is_some());
+        }
+        assert_ne!(generate_seeded(6, 6, 8, 0), generate_seeded(6, 6, 8, 1));
+    }
+
+    #[test]
+    fn generated_boards_are_valid_and_solvable() {
+        for (width, height, pieces) in [(3, 1, 1), (4, 3, 3), (5, 5, 6), (6, 6, 8), (7, 4, 6)] {
+            for seed in 0..10 {
+                let board = generate_seeded(width, height, pieces, seed);
+                assert_eq!(board.signature(), (width, height, pieces as usize));
+                assert_eq!(board.pieces().iter().filter(|p| p.is_marked()).count(), 1);
+                assert_eq!(board.check_invariants(), Ok(()));
+                assert!(!board.is_won);
+                assert!(crate::solve_path(board).is_some());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "don't fit")]
+    fn too_many_pieces_panic_without_overflowing() {
+        generate_seeded(65536, 32768, u32::MAX, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many tiles")]
+    fn too_many_tiles_panic_without_overflowing() {
+        generate_seeded(70000, 70000, 8, 0);
+    }
+}
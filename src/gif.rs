@@ -0,0 +1,97 @@
+use crate::board::*;
+use ::gif::{Encoder, Frame, Repeat};
+use std::convert::TryFrom;
+
+/// The number of hundredths of a second each board is shown
+const FRAME_DELAY: u16 = 50;
+/// The colors of the frames, indexed by the pixels
+const PALETTE: [[u8; 3]; 12] = [
+    [0xee, 0xee, 0xee], // background
+    [0x44, 0x44, 0x44], // walls
+    [0x98, 0xdf, 0x8a], // goal
+    [0xd6, 0x27, 0x28], // marked pieces
+    [0x1f, 0x77, 0xb4],
+    [0xff, 0x7f, 0x0e],
+    [0x2c, 0xa0, 0x2c],
+    [0x94, 0x67, 0xbd],
+    [0x8c, 0x56, 0x4b],
+    [0xe3, 0x77, 0xc2],
+    [0xbc, 0xbd, 0x22],
+    [0x17, 0xbe, 0xcf],
+];
+const BACKGROUND: u8 = 0;
+const WALL: u8 = 1;
+const GOAL: u8 = 2;
+const MARKED: u8 = 3;
+/// The index of the first color of the unmarked pieces
+const FIRST_PIECE: u8 = 4;
+
+/// Draw a solution as an animated GIF with one frame for every board along
+/// it, where each tile is `cell_px` pixels wide. The marked piece is red and
+/// the goal tile is green. The moves are assumed to be legal, like in
+/// `Board::play`.
+///
+/// Panics if the image would be more than `u16::MAX` pixels wide or high.
+pub fn solution_to_gif(start: &Board, moves: &[Move], cell_px: u32) -> Vec<u8> {
+    let size = |tiles: u32| {
+        u16::try_from(tiles * cell_px).expect("the GIF is too large to encode")
+    };
+    let (width, height) = (size(start.width), size(start.height));
+    let palette: Vec<u8> = PALETTE.iter().flatten().copied().collect();
+
+    let mut gif = Vec::new();
+    {
+        // writing to a vector can't fail
+        let mut encoder = Encoder::new(&mut gif, width, height, &palette).unwrap();
+        encoder.set_repeat(Repeat::Infinite).unwrap();
+
+        let mut board = start.clone();
+        for i in 0..=moves.len() {
+            let mut frame = Frame::from_indexed_pixels(width, height, draw(&board, cell_px), None);
+            frame.delay = FRAME_DELAY;
+            encoder.write_frame(&frame).unwrap();
+            if let Some(mov) = moves.get(i) {
+                board = board.play(mov);
+            }
+        }
+    }
+    gif
+}
+
+/// The palette indexes of every pixel of the board, row by row
+fn draw(board: &Board, cell_px: u32) -> Vec<u8> {
+    let width = board.width * cell_px;
+    let mut pixels = vec![BACKGROUND; (width * board.height * cell_px) as usize];
+    let gap = cell_px / 16;
+    let mut fill = |(x, y): Tile, (w, h): (u32, u32), color: u8| {
+        for row in y..y + h {
+            let start = (row * width + x) as usize;
+            pixels[start..start + w as usize].fill(color);
+        }
+    };
+
+    for &(x, y) in &board.walls {
+        fill((x * cell_px, y * cell_px), (cell_px, cell_px), WALL);
+    }
+    let (gx, gy) = board.goal;
+    fill((gx * cell_px, gy * cell_px), (cell_px, cell_px), GOAL);
+
+    for (i, piece) in board.pieces().iter().enumerate() {
+        let (w, h) = match piece.direction() {
+            Direction::Horizontal => (piece.size() * cell_px, cell_px),
+            Direction::Vertical => (cell_px, piece.size() * cell_px),
+        };
+        let color = if piece.is_marked() {
+            MARKED
+        } else {
+            FIRST_PIECE + (i % (PALETTE.len() - FIRST_PIECE as usize)) as u8
+        };
+        let (x, y) = piece.location();
+        fill(
+            (x * cell_px + gap, y * cell_px + gap),
+            (w - 2 * gap, h - 2 * gap),
+            color,
+        );
+    }
+    pixels
+}
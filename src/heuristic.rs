@@ -0,0 +1,180 @@
+use crate::board::*;
+
+/// An estimate of the number of moves left to win a board, which guides
+/// `ida_star_with`. The estimate has to be admissible, never more than the
+/// number of moves of an optimal solution, for the solutions to be optimal.
+/// Any function from a board to a number is a heuristic too.
+pub trait Heuristic {
+    /// Estimate the number of moves left to win the board
+    fn estimate(&self, board: &Board) -> u32;
+}
+
+impl<F: Fn(&Board) -> u32> Heuristic for F {
+    fn estimate(&self, board: &Board) -> u32 {
+        self(board)
+    }
+}
+
+/// Count a move for every marked piece that hasn't reached its goal and
+/// one for every piece between it and the goal, which all have to move.
+/// This is the heuristic that `ida_star` uses.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct BlockingCount;
+
+impl Heuristic for BlockingCount {
+    fn estimate(&self, board: &Board) -> u32 {
+        board.min_moves_left()
+    }
+}
+
+/// Count the moves of `BlockingCount`, and one more if a piece in the way
+/// can't get out of it before some piece that wasn't counted moves, which
+/// is when every way out of the path is full or runs off the board
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct SecondOrderBlocking;
+
+impl Heuristic for SecondOrderBlocking {
+    fn estimate(&self, board: &Board) -> u32 {
+        if !board.topology().is_rectangular() {
+            return board.min_moves_left();
+        }
+        board
+            .pieces()
+            .iter()
+            .filter(|p| p.is_marked() && !board.reached_goal(p))
+            .map(|marked| {
+                if marked.size() == 1 {
+                    return 1;
+                }
+                let blocking = board.pieces_between(marked);
+                let stuck = blocking
+                    .iter()
+                    .any(|&id| needs_another_piece(board, &board.pieces()[id], marked, &blocking));
+                1 + blocking.len() as u32 + stuck as u32
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Check if a piece in the path of a marked piece can't leave the path, on
+/// either side, without another piece moving first that isn't the marked
+/// piece or one of the other pieces in the path. Only the pieces across
+/// the path are checked, the others aren't known to have to go to a side.
+fn needs_another_piece(board: &Board, piece: &Piece, marked: &Piece, blocking: &[usize]) -> bool {
+    if piece.size() == 1 || piece.direction() == marked.direction() {
+        return false;
+    }
+
+    // the tiles of the line the piece moves along, given by their index
+    let ((x, y), size) = (piece.location(), piece.size());
+    let (path, start, length) = match marked.direction() {
+        Direction::Horizontal => (marked.location().1, y, board.height),
+        Direction::Vertical => (marked.location().0, x, board.width),
+    };
+    let tile = |i| match marked.direction() {
+        Direction::Horizontal => (x, i),
+        Direction::Vertical => (i, y),
+    };
+
+    // a side is clear if the piece fits between the path and the edge,
+    // and nothing but the counted pieces stands where it has to pass
+    let clear = |range: core::ops::Range<u32>| {
+        range.map(tile).all(|t| {
+            !board.walls.contains(&t)
+                && board
+                    .piece_at(t)
+                    .is_none_or(|id| id == marked.id() || blocking.contains(&id))
+        })
+    };
+    let before = path >= size && clear(path - size..start);
+    let after = path + size < length && clear(start + size..path + size + 1);
+    !before && !after
+}
+
+/// Count the straight slides the marked pieces need to reach their goals if
+/// there were no other pieces, one for a piece in line with its goal and
+/// two for a single tile that has to turn a corner. It is the Manhattan
+/// distance to the goal measured in moves rather than tiles.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct GoalDistance;
+
+impl Heuristic for GoalDistance {
+    fn estimate(&self, board: &Board) -> u32 {
+        board
+            .pieces()
+            .iter()
+            .filter(|p| p.is_marked() && !board.reached_goal(p))
+            .map(|p| {
+                let (x, y) = p.location();
+                let (goal_x, goal_y) = p.goal().unwrap_or(board.goal);
+                let in_line = x == goal_x || y == goal_y;
+                match p.size() {
+                    1 if !in_line && board.topology().is_rectangular() => 2,
+                    _ => 1,
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::BoardHasher;
+    use std::collections::{HashMap, VecDeque};
+
+    /// The number of moves an optimal solution takes from every board
+    /// reachable from the start, found backwards from the won boards
+    fn moves_left(start: Board) -> HashMap<Board, u32, BoardHasher> {
+        let mut queue: VecDeque<Board> = start.reachable().filter(|b| b.is_won).collect();
+        let mut moves = HashMap::default();
+        moves.extend(queue.iter().map(|board| (board.clone(), 0)));
+        while let Some(board) = queue.pop_front() {
+            let left = moves[&board] + 1;
+            for (future, _) in board.future_boards() {
+                if !moves.contains_key(&future) {
+                    moves.insert(future.clone(), left);
+                    queue.push_back(future);
+                }
+            }
+        }
+        moves
+    }
+
+    fn assert_admissible(start: Board) {
+        let heuristics: [(&str, &dyn Heuristic); 3] = [
+            ("blocking count", &BlockingCount),
+            ("second order blocking", &SecondOrderBlocking),
+            ("goal distance", &GoalDistance),
+        ];
+        let moves = moves_left(start);
+        assert!(!moves.is_empty());
+        for (board, &left) in &moves {
+            for (name, heuristic) in &heuristics {
+                let estimate = heuristic.estimate(board);
+                assert!(estimate <= left, "{} says {} for\n{}", name, estimate, board);
+                assert_eq!(estimate == 0, left == 0, "{} for\n{}", name, board);
+            }
+        }
+    }
+
+    #[test]
+    fn heuristics_are_admissible() {
+        assert_admissible(Board::sample());
+        for seed in 0..5 {
+            assert_admissible(crate::generate_seeded(5, 5, 6, seed));
+        }
+        let pieces = vec![Piece::marked((0, 0), 1, Direction::Horizontal)];
+        let corner = Board::new(3, 3, (2, 2), pieces);
+        assert_eq!(GoalDistance.estimate(&corner), 2);
+        assert_admissible(corner);
+    }
+
+    #[test]
+    fn closures_are_heuristics() {
+        let pieces = |board: &Board| board.pieces().len() as u32;
+        assert_eq!(pieces.estimate(&Board::sample()), 12);
+    }
+}
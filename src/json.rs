@@ -0,0 +1,67 @@
+use crate::board::*;
+use serde::Serialize;
+
+/// A move of a solution and the board after it
+#[derive(Serialize)]
+struct Step {
+    piece: usize,
+    direction: &'static str,
+    distance: u32,
+    board: String,
+}
+
+/// Write a solution as a JSON array with a step for every move, holding
+/// the piece id, the direction and distance of the move and the board
+/// after it as an ASCII grid like `Display` draws it.
+/// The moves are assumed to be legal, like in `Board::play`.
+pub fn solution_to_json(start: &Board, moves: &[Move]) -> String {
+    let mut board = start.clone();
+    let steps: Vec<Step> = moves
+        .iter()
+        .map(|mov| {
+            board = board.play(mov);
+            let (direction, distance) = match *mov {
+                Move::Left(_, steps) => ("left", steps),
+                Move::Right(_, steps) => ("right", steps),
+                Move::Up(_, steps) => ("up", steps),
+                Move::Down(_, steps) => ("down", steps),
+            };
+            Step {
+                piece: mov.get_piece(),
+                direction,
+                distance,
+                board: board.to_string(),
+            }
+        })
+        .collect();
+
+    // the steps contain nothing that can't be serialized
+    serde_json::to_string(&steps).expect("the steps can be serialized")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_of_the_sample_solution() {
+        let board = Board::sample();
+        let solution = crate::solve_path(board.clone()).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&solution_to_json(&board, &solution)).unwrap();
+        let steps = json.as_array().unwrap();
+        assert_eq!(steps.len(), solution.len());
+
+        let first = &steps[0];
+        assert_eq!(first["piece"], solution[0].get_piece());
+        assert_eq!(first["distance"], solution[0].steps());
+        assert_eq!(first["board"], board.play(&solution[0]).to_string());
+        let last = board.replay(&solution).unwrap();
+        assert_eq!(steps[steps.len() - 1]["board"], last.to_string());
+    }
+
+    #[test]
+    fn no_moves_is_an_empty_array() {
+        assert_eq!(solution_to_json(&Board::sample(), &[]), "[]");
+    }
+}
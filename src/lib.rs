@@ -0,0 +1,90 @@
+//! A solver for sliding block puzzles such as Rush Hour
+//!
+//! Without the `std` feature only the boards and moves are built,
+//! which only need `alloc`. This is checked by `cargo check --lib
+//! --no-default-features`, which the `no_std` test runs, and on a target
+//! without std by `cargo build --lib --no-default-features --target
+//! thumbv7em-none-eabihf`. The tests of the boards and moves run without
+//! std with `cargo test --lib --no-default-features`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod batch;
+mod bitboard;
+mod board;
+mod builder;
+mod bytes;
+#[cfg(feature = "std")]
+mod difficulty;
+mod error;
+#[cfg(feature = "std")]
+mod file;
+#[cfg(feature = "std")]
+mod generate;
+#[cfg(feature = "gif")]
+mod gif;
+#[cfg(feature = "std")]
+mod heuristic;
+#[cfg(feature = "serde")]
+mod json;
+mod notation;
+#[cfg(feature = "std")]
+mod reachable;
+#[cfg(feature = "color")]
+mod render;
+mod session;
+#[cfg(feature = "std")]
+mod solutions;
+#[cfg(feature = "std")]
+mod solver;
+#[cfg(feature = "svg")]
+mod svg;
+mod topology;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(feature = "std")]
+pub use batch::{analyze_batch, BatchReport};
+pub use bitboard::BitBoard;
+pub use board::{
+    Board, BoardError, Direction, Goal, Move, MoveError, ParseError, Piece, ReplayError, Side, Tile,
+    TileSet, Transition, WinMode,
+};
+pub use builder::BoardBuilder;
+#[cfg(feature = "std")]
+pub use difficulty::{
+    count_optimal_solutions, difficulty, has_unique_solution, piece_move_counts, BranchingScope,
+    DifficultyRating,
+};
+pub use error::Error;
+#[cfg(feature = "std")]
+pub use file::LoadError;
+#[cfg(feature = "std")]
+pub use generate::{generate, generate_seeded, scramble};
+#[cfg(feature = "gif")]
+pub use gif::solution_to_gif;
+#[cfg(feature = "std")]
+pub use heuristic::{BlockingCount, GoalDistance, Heuristic, SecondOrderBlocking};
+#[cfg(feature = "serde")]
+pub use json::solution_to_json;
+pub use notation::{compress_moves, decompress_moves, moves_from_notation, moves_to_notation};
+#[cfg(feature = "std")]
+pub use reachable::ReachableIter;
+pub use session::GameSession;
+#[cfg(feature = "std")]
+pub use solutions::Solutions;
+#[cfg(feature = "std")]
+pub use solver::{
+    beam_search, bidirectional_solve, dfs_solve, dijkstra_solve, iddfs_solve, ida_star,
+    ida_star_with, solve, solve_bitboard, solve_canonical, solve_limited, solve_min_pieces,
+    solve_path, solve_single_steps, solve_with_cache, solve_with_progress, solve_with_state_limit,
+    solve_with_stats, solve_with_timeout, BoardHasher, Hint, MoveCache, SearchStats, SolveError,
+    Visited,
+};
+#[cfg(feature = "svg")]
+pub use svg::solution_to_svg;
+pub use topology::{RectTopology, Topology, ToroidalTopology};
+#[cfg(feature = "wasm")]
+pub use wasm::solve_ascii;
@@ -1,83 +1,359 @@
-use std::collections::HashMap;
-use std::env;
-use std::time::Instant;
-mod board;
-use board::*;
+use blocked::*;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "tui")]
+mod play;
+
+/// Exit codes of the binary, clap exits with 2 on invalid arguments
+const EXIT_ERROR: i32 = 1;
+const EXIT_UNSOLVABLE: i32 = 3;
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// ANSI escape codes used by the animation
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// Solve and generate sliding block puzzles such as Rush Hour.
+///
+/// Exits with 0 on success, 1 if the input can't be read or parsed,
+/// 2 on invalid arguments, 3 if the board has no solution and 130 if
+/// an animation is interrupted.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// How solutions and boards are printed
+    #[arg(long, value_enum, global = true, default_value_t = Format::Ascii)]
+    format: Format,
+    /// Print more about what is going on
+    #[arg(long, global = true)]
+    verbose: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a board, read from the file or stdin as an ASCII grid where
+    /// `+` is the goal, or as JSON from a .json file. The sample board is
+    /// solved if no file is given and nothing is piped to stdin.
+    Solve {
+        file: Option<PathBuf>,
+        /// Play the solution in the terminal, one board after the other
+        #[arg(long)]
+        animate: bool,
+        /// The time each board of the animation is shown for
+        #[arg(long, default_value_t = 500)]
+        delay_ms: u64,
+    },
+    /// Generate a random solvable board
+    Generate {
+        /// The size of the board, as WIDTHxHEIGHT
+        #[arg(long, value_parser = parse_size, default_value = "6x6")]
+        size: (u32, u32),
+        /// The number of pieces, the marked one included
+        #[arg(long, default_value_t = 10)]
+        pieces: u32,
+        /// Generate the same board every time the seed is given
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Play a board by hand in the terminal, read from the file like the
+    /// solve command does. The sample board is played if no file is given.
+    #[cfg(feature = "tui")]
+    Play { file: Option<PathBuf> },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    /// Boards as grids, every board along a solution with --verbose
+    Ascii,
+    /// Boards and moves as JSON
+    Json,
+    /// Moves in compact notation such as "A R2", boards as grids
+    Notation,
+}
 
 fn main() {
-    let mut previous_boards = HashMap::new();
-
-    // Sample board
-    let board3 = Board::new(6,6, (5,2), vec![
-        Piece::marked((0,2), 2, Direction::Horizontal),
-        Piece::new((0,3), 2, Direction::Horizontal),
-        Piece::new((0,4), 2, Direction::Vertical),
-        Piece::new((1,4), 2, Direction::Vertical),
-        Piece::new((2,0), 2, Direction::Vertical),
-        Piece::new((2,2), 2, Direction::Vertical),
-        Piece::new((2,4), 2, Direction::Horizontal),
-        Piece::new((2,5), 2, Direction::Horizontal),
-        Piece::new((3,0), 3, Direction::Horizontal),
-        Piece::new((3,3), 2, Direction::Horizontal),
-        Piece::new((3,1), 2, Direction::Vertical),
-        Piece::new((5,2), 3, Direction::Vertical),
-    ]);
-
-    let now = Instant::now();
-    
-    let (mut board, steps) = solve(board3, &mut previous_boards);
-    println!("Total steps: {}", steps);
-    
-    let mut history = vec![];
-    while let Some(entry) = previous_boards.get(&board) {
-        
-        if let Some(prev_move) = entry {
-            history.push(prev_move.to_owned());
-            board = board.undo(prev_move);
-        } else { break; }
-    }
-    history.reverse();
-    println!("Total time: {} ms", now.elapsed().as_millis());
-
-    let args: Vec<String> = env::args().skip(1).collect();
-    
-    if !args.is_empty() && args[0] == "--verbose" {
-        history.iter().for_each(|step| println!("{}", step));
-    }
-   
-}
-
-
-/// Solve a given board and return the number of steps and the final board
-fn solve(start: Board, visited: &mut HashMap<Board, Option<Move>>) -> (Board, u32) {
-    let mut boards = start.future_boards();
-    visited.insert(start, None);
-    let mut steps = 0;
-    loop {
-        let mut new_boards = vec![];
-        // remove the board configurations that we already have visited
-        // and add the new ones to our transposition table
-        boards = boards
-            .into_iter()
-            .filter(|(board, mov)| {
-                if visited.contains_key(board) {
-                    false
-                } else {
-                    visited.insert(board.to_owned(), Some(*mov));
-                    true
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Solve {
+        file: None,
+        animate: false,
+        delay_ms: 0,
+    });
+    match command {
+        Command::Solve {
+            file,
+            animate,
+            delay_ms,
+        } => {
+            let animation = animate.then(|| Duration::from_millis(delay_ms));
+            solve_command(file, cli.format, cli.verbose, animation)
+        }
+        Command::Generate {
+            size,
+            pieces,
+            seed,
+        } => generate_command(size, pieces, seed, cli.format),
+        #[cfg(feature = "tui")]
+        Command::Play { file } => play_command(file),
+    }
+}
+
+fn solve_command(
+    file: Option<PathBuf>,
+    format: Format,
+    verbose: bool,
+    animation: Option<Duration>,
+) {
+    let start = read_board(file);
+    let (history, stats) = solve_with_stats(start.clone());
+    let history = match history {
+        Some(history) => history,
+        None => {
+            println!("The board has no solution");
+            process::exit(EXIT_UNSOLVABLE);
+        }
+    };
+
+    match format {
+        Format::Ascii => {
+            if let Some(delay) = animation {
+                animate(&start, &history, delay);
+            }
+            println!("Total steps: {}", stats.depth);
+            println!("Tiles traveled: {}", stats.cell_count);
+            println!("States explored: {}", stats.states_explored);
+            println!("Total time: {} ms", stats.elapsed.as_millis());
+
+            if verbose && animation.is_none() {
+                let mut board = start;
+                println!("{}\n", draw(&board));
+                for step in &history {
+                    let next = board.play(step);
+                    println!("{}\n{}\n", step, draw_step(&board, &next));
+                    board = next;
                 }
-            })
-            .collect();
+            }
+        }
+        Format::Json => println!("{}", solution_to_json(&start, &history)),
+        Format::Notation => println!("{}", moves_to_notation(&history, &start)),
+    }
 
-        steps += 1;
+    // keep stdout clean for scripts, the numbers go to stderr
+    if verbose && format != Format::Ascii {
+        eprintln!(
+            "{} steps, {} states explored in {} ms",
+            stats.depth,
+            stats.states_explored,
+            stats.elapsed.as_millis()
+        );
+    }
+}
+
+/// Clear the terminal and draw every board along the solution, waiting
+/// for the delay between them. The cursor is hidden meanwhile and shown
+/// again when the animation ends or is interrupted with Ctrl-C.
+fn animate(start: &Board, moves: &[Move], delay: Duration) {
+    let boards = start
+        .playthrough(moves)
+        .expect("the moves of a solution can be played");
+
+    // the handler can only be set once, which is all the binary needs
+    let _ = ctrlc::set_handler(|| {
+        show_cursor();
+        process::exit(EXIT_INTERRUPTED);
+    });
+    print!("{}", HIDE_CURSOR);
+    let _cursor = CursorGuard;
+
+    for (i, board) in boards.iter().enumerate() {
+        let (caption, drawing) = match i {
+            0 => ("Start".to_string(), draw(board)),
+            _ => (
+                format!("{}/{}: {}", i, moves.len(), moves[i - 1]),
+                draw_step(&boards[i - 1], board),
+            ),
+        };
+        print!("{}{}\n\n{}\n\n", CLEAR_SCREEN, caption, drawing);
+        let _ = io::stdout().flush();
+        if i < moves.len() {
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Shows the cursor when dropped, so that it comes back after a panic too
+struct CursorGuard;
 
-        for (board, _) in boards {
-            if board.is_won { return (board, steps); }
-            new_boards.append(
-                &mut board.future_boards()
-            );
+impl Drop for CursorGuard {
+    fn drop(&mut self) {
+        show_cursor();
+    }
+}
+
+fn show_cursor() {
+    print!("{}", SHOW_CURSOR);
+    let _ = io::stdout().flush();
+}
+
+/// Play a board in the terminal and say how many moves it took if it was won
+#[cfg(feature = "tui")]
+fn play_command(file: Option<PathBuf>) {
+    let board = read_board(file);
+    match play::play(board) {
+        Ok(session) if session.board().is_won => {
+            println!("Solved in {} moves", session.history().len());
         }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Could not play in the terminal: {}", e);
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
 
-        boards = new_boards;
+fn generate_command((width, height): (u32, u32), pieces: u32, seed: Option<u64>, format: Format) {
+    if let Err(e) = check_generate_size((width, height), pieces) {
+        eprintln!("{}", e);
+        process::exit(EXIT_ERROR);
+    }
+
+    let board = match seed {
+        Some(seed) => generate_seeded(width, height, pieces, seed),
+        None => generate(width, height, pieces, &mut rand::thread_rng()),
+    };
+    match format {
+        Format::Json => println!("{}", to_json(&board)),
+        Format::Ascii | Format::Notation => println!("{}", draw(&board)),
+    }
+}
+
+/// Check that a board of the size can be generated with the number of
+/// pieces, which `generate` panics on otherwise
+fn check_generate_size((width, height): (u32, u32), pieces: u32) -> Result<(), String> {
+    if pieces == 0 {
+        return Err("A board needs at least one piece, the marked one".to_string());
+    }
+    // counted in a u64 so that large sizes don't overflow
+    let tiles = u64::from(width) * u64::from(height);
+    if width < 3 || height < 1 || tiles > u64::from(u32::MAX) || u64::from(pieces) * 2 >= tiles {
+        return Err(format!("{} pieces don't fit on a {}x{} board", pieces, width, height));
+    }
+    Ok(())
+}
+
+/// Parse a size written as WIDTHxHEIGHT
+fn parse_size(size: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("'{}' is not a size like 6x6", size);
+    let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    // boards and moves contain nothing that can't be serialized
+    serde_json::to_string(value).expect("the value can be serialized")
+}
+
+/// Read the board from the file or piped to stdin, or the sample board if
+/// there is neither. Exits if the board can't be read or parsed.
+fn read_board(file: Option<PathBuf>) -> Board {
+    match &file {
+        Some(path) => Board::from_file(path).unwrap_or_else(|e| {
+            eprintln!("Could not load {}: {}", path.display(), e);
+            process::exit(EXIT_ERROR);
+        }),
+        None => {
+            let text = read_stdin();
+            if text.trim().is_empty() {
+                return Board::sample();
+            }
+            Board::from_drawing(&text).unwrap_or_else(|e| {
+                eprintln!("Could not parse the board: {}", e);
+                process::exit(EXIT_ERROR);
+            })
+        }
+    }
+}
+
+/// Read all of stdin, nothing is read if it is a terminal rather than a pipe
+fn read_stdin() -> String {
+    let mut text = String::new();
+    if !io::stdin().is_terminal() {
+        if let Err(e) = io::stdin().read_to_string(&mut text) {
+            eprintln!("Could not read stdin: {}", e);
+            process::exit(EXIT_ERROR);
+        }
+    }
+    text
+}
+
+/// Draw a board for the terminal, in color if the `color` feature is enabled
+#[cfg(feature = "color")]
+fn draw(board: &Board) -> String {
+    board.render_colored()
+}
+
+#[cfg(not(feature = "color"))]
+fn draw(board: &Board) -> String {
+    board.to_string()
+}
+
+/// Draw the board after a move, with the moved piece highlighted if the
+/// `color` feature is enabled
+#[cfg(feature = "color")]
+fn draw_step(before: &Board, after: &Board) -> String {
+    after.render_diff(before)
+}
+
+#[cfg(not(feature = "color"))]
+fn draw_step(_before: &Board, after: &Board) -> String {
+    after.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("6x6"), Ok((6, 6)));
+        assert_eq!(parse_size("8x6"), Ok((8, 6)));
+        assert!(parse_size("6").is_err());
+        assert!(parse_size("6x").is_err());
+        assert!(parse_size("-1x6").is_err());
+    }
+
+    #[test]
+    fn generate_sizes() {
+        assert_eq!(check_generate_size((6, 6), 10), Ok(()));
+        assert_eq!(check_generate_size((3, 1), 1), Ok(()));
+        assert!(check_generate_size((6, 6), 0).is_err());
+        assert!(check_generate_size((2, 6), 1).is_err());
+        assert!(check_generate_size((6, 6), 18).is_err());
+        // the tiles don't fit in a u32
+        assert!(check_generate_size((70000, 70000), 10).is_err());
+        assert!(check_generate_size((u32::MAX, u32::MAX), u32::MAX).is_err());
+    }
+
+    #[test]
+    fn arguments() {
+        let cli = Cli::try_parse_from(["blocked", "generate", "--size", "8x6", "--pieces", "4"]);
+        match cli.map(|cli| cli.command) {
+            Ok(Some(Command::Generate { size, pieces, .. })) => {
+                assert_eq!((size, pieces), ((8, 6), 4));
+            }
+            _ => panic!("the generate command wasn't parsed"),
+        }
+        assert!(Cli::try_parse_from(["blocked", "generate", "--size", "8"]).is_err());
+        assert!(Cli::try_parse_from(["blocked", "--format", "xml"]).is_err());
     }
 }
@@ -0,0 +1,201 @@
+use crate::board::*;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+impl Move {
+    /// Write the move as the piece, the direction and the distance,
+    /// e.g. `A R2` to move piece `a` two steps to the right. Pieces are
+    /// written with their letters in the drawing of the board upper-cased,
+    /// `X` for the marked piece, and as their id if they have no letter
+    /// of their own, past `z` or when several pieces would be an `X`.
+    pub fn to_notation(&self, board: &Board) -> String {
+        let (id, dir, steps) = match *self {
+            Move::Left(id, steps) => (id, 'L', steps),
+            Move::Right(id, steps) => (id, 'R', steps),
+            Move::Up(id, steps) => (id, 'U', steps),
+            Move::Down(id, steps) => (id, 'D', steps),
+        };
+        format!("{} {}{}", piece_name(board, id), dir, steps)
+    }
+
+    /// Parse a move written like `to_notation` does,
+    /// checking that the piece is on the given board
+    pub fn from_notation(notation: &str, board: &Board) -> Result<Move, ParseError> {
+        let invalid = || ParseError::InvalidNotation(notation.to_owned());
+        let mut parts = notation.split_whitespace();
+        let (piece, mov) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(piece), Some(mov), None) => (piece, mov),
+            _ => return Err(invalid()),
+        };
+
+        let id = (0..board.pieces().len())
+            .find(|&id| piece_name(board, id) == piece)
+            .ok_or_else(|| ParseError::UnknownPiece(piece.to_owned()))?;
+
+        let mut chars = mov.chars();
+        let dir = chars.next().ok_or_else(invalid)?;
+        let steps = chars.as_str().parse().map_err(|_| invalid())?;
+        match dir {
+            'L' => Ok(Move::Left(id, steps)),
+            'R' => Ok(Move::Right(id, steps)),
+            'U' => Ok(Move::Up(id, steps)),
+            'D' => Ok(Move::Down(id, steps)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// The name of a piece in notation, see `Move::to_notation`
+fn piece_name(board: &Board, id: usize) -> String {
+    let pieces = board.pieces();
+    if pieces[id].is_marked() {
+        let marked = pieces.iter().filter(|p| p.is_marked()).count();
+        return if marked == 1 { "X".to_string() } else { id.to_string() };
+    }
+    match piece_letter(id).to_ascii_uppercase() {
+        // pieces past `z` have no letter, and an `X` is the marked piece
+        letter @ 'A'..='Z' if letter != 'X' => letter.to_string(),
+        _ => id.to_string(),
+    }
+}
+
+/// Write a list of moves played from the board in notation,
+/// separated by spaces
+pub fn moves_to_notation(moves: &[Move], board: &Board) -> String {
+    moves
+        .iter()
+        .map(|mov| mov.to_notation(board))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a list of moves written by `moves_to_notation`
+pub fn moves_from_notation(notation: &str, board: &Board) -> Result<Vec<Move>, ParseError> {
+    let words: Vec<&str> = notation.split_whitespace().collect();
+    words
+        .chunks(2)
+        .map(|mov| Move::from_notation(&mov.join(" "), board))
+        .collect()
+}
+
+/// Merge consecutive moves of the same piece in the same direction into
+/// one move, e.g. the single steps of `solve_single_steps` into slides
+pub fn compress_moves(moves: &[Move]) -> Vec<Move> {
+    let mut compressed: Vec<Move> = vec![];
+    for &mov in moves {
+        match compressed.last_mut() {
+            // moves of the same piece and direction only differ in steps
+            Some(last) if last.with_steps(1) == mov.with_steps(1) => {
+                *last = last.with_steps(last.steps() + mov.steps());
+            }
+            _ => compressed.push(mov),
+        }
+    }
+    compressed
+}
+
+/// Split every move into moves of a single step, the inverse of
+/// `compress_moves`. Moves of zero steps are left out.
+pub fn decompress_moves(moves: &[Move]) -> Vec<Move> {
+    moves
+        .iter()
+        .flat_map(|mov| (0..mov.steps()).map(move |_| mov.with_steps(1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_match_the_drawing() {
+        let board = Board::sample();
+        let drawing: Vec<Vec<char>> = board
+            .to_string()
+            .lines()
+            .map(|row| row.chars().collect())
+            .collect();
+        for mov in board.all_moves() {
+            let (x, y) = board.pieces()[mov.get_piece()].location();
+            let letter = drawing[y as usize][x as usize].to_ascii_uppercase();
+            assert!(mov.to_notation(&board).starts_with(letter));
+        }
+        assert_eq!(Move::Right(3, 2).to_notation(&board), "X R2");
+        assert_eq!(Move::Up(5, 1).to_notation(&board), "F U1");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn round_trip_sample_solution() {
+        let board = Board::sample();
+        let solution = crate::solve_path(board.clone()).unwrap();
+        let notation = moves_to_notation(&solution, &board);
+        assert_eq!(moves_from_notation(&notation, &board).unwrap(), solution);
+    }
+
+    #[test]
+    fn pieces_without_a_letter() {
+        // two marked pieces and more pieces than letters
+        let mut pieces = vec![
+            Piece::marked((0, 0), 1, Direction::Horizontal),
+            Piece::marked_with_goal((1, 0), 1, Direction::Horizontal, (1, 1)),
+        ];
+        pieces.extend((2..30).map(|x| Piece::new((x, 0), 1, Direction::Vertical)));
+        let board = Board::new(30, 2, (0, 1), pieces);
+
+        for (id, name) in [(0, "0"), (1, "1"), (2, "C"), (23, "23"), (25, "Z"), (29, "29")] {
+            let mov = Move::Down(id, 1);
+            assert_eq!(mov.to_notation(&board), format!("{} D1", name));
+            assert_eq!(Move::from_notation(&mov.to_notation(&board), &board), Ok(mov));
+        }
+    }
+
+    #[test]
+    fn invalid_notation() {
+        let board = Board::sample();
+        // the marked piece is drawn as an `X`, not with its own letter
+        assert_eq!(
+            Move::from_notation("D R1", &board),
+            Err(ParseError::UnknownPiece("D".to_string()))
+        );
+        assert_eq!(
+            Move::from_notation("Q R1", &board),
+            Err(ParseError::UnknownPiece("Q".to_string()))
+        );
+        for notation in ["X", "X R", "X Q1", "X R1 R1", "X Rx"] {
+            assert_eq!(
+                Move::from_notation(notation, &board),
+                Err(ParseError::InvalidNotation(notation.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn merge_and_split_moves() {
+        let moves = [Move::Right(1, 1), Move::Right(1, 2), Move::Left(1, 1), Move::Right(2, 1)];
+        assert_eq!(
+            compress_moves(&moves),
+            [Move::Right(1, 3), Move::Left(1, 1), Move::Right(2, 1)]
+        );
+        assert_eq!(decompress_moves(&[Move::Up(0, 2), Move::Down(3, 0)]), [Move::Up(0, 1); 2]);
+        assert!(compress_moves(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn compressed_moves_replay_the_same() {
+        let board = Board::sample();
+        let solution = crate::solve_path(board.clone()).unwrap();
+        let end = board.replay(&solution).unwrap();
+
+        let single_steps = decompress_moves(&solution);
+        assert!(single_steps.iter().all(|mov| mov.steps() == 1));
+        assert_eq!(board.replay(&single_steps), Ok(end.clone()));
+        // an optimal solution never moves a piece the same way twice in a row
+        assert_eq!(compress_moves(&single_steps), solution);
+        assert_eq!(board.replay(&compress_moves(&single_steps)), Ok(end));
+    }
+}
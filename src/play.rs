@@ -0,0 +1,295 @@
+use blocked::*;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use std::io::{self, Write};
+
+const HELP: &str = "arrows: select or slide  space: grab or drop  \
+                    u: undo  r: redo  h: hint  q: quit";
+
+/// A board being played in the terminal
+struct Game {
+    session: GameSession,
+    /// The id of the selected piece
+    selected: usize,
+    /// The number of moves played when the selected piece was grabbed,
+    /// the arrows slide the piece rather than select another one meanwhile
+    grabbed: Option<usize>,
+    /// What happened last, shown under the board
+    message: String,
+}
+
+/// Play a board by hand in the terminal until the player quits, and return
+/// the session with the moves played. The terminal is taken over meanwhile
+/// and given back as it was afterwards.
+pub fn play(board: Board) -> io::Result<GameSession> {
+    terminal::enable_raw_mode()?;
+    let _terminal = TerminalGuard;
+    let mut out = io::stdout();
+    execute!(out, EnterAlternateScreen, Hide)?;
+
+    let selected = board
+        .pieces()
+        .iter()
+        .position(Piece::is_marked)
+        .unwrap_or(0);
+    let mut game = Game {
+        session: GameSession::new(board),
+        selected,
+        grabbed: None,
+        message: String::new(),
+    };
+    loop {
+        draw(&mut out, &game)?;
+        let quit = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => !game.handle(key),
+            _ => false,
+        };
+        if quit {
+            return Ok(game.session);
+        }
+    }
+}
+
+impl Game {
+    /// React to a key, returns false once the player quits
+    fn handle(&mut self, key: KeyEvent) -> bool {
+        let won = self.session.board().is_won;
+        match key.code {
+            // raw mode turns Ctrl-C into a key like any other
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc if self.grabbed.is_none() => return false,
+            KeyCode::Esc => self.grabbed = None,
+            KeyCode::Char(' ') | KeyCode::Enter if self.grabbed.is_some() => self.grabbed = None,
+            KeyCode::Char(' ') | KeyCode::Enter if !won => {
+                self.grabbed = Some(self.session.history().len());
+            }
+            KeyCode::Char('u') => {
+                self.grabbed = None;
+                self.message = match self.session.undo() {
+                    Some(mov) => format!("Took back: {}", mov),
+                    None => "There is nothing to undo".to_string(),
+                };
+            }
+            KeyCode::Char('r') => {
+                self.grabbed = None;
+                self.message = match self.session.redo() {
+                    Some(mov) => format!("Played again: {}", mov),
+                    None => "There is nothing to redo".to_string(),
+                };
+            }
+            KeyCode::Char('h') => self.hint(),
+            KeyCode::Left => self.arrow(Side::Left),
+            KeyCode::Right => self.arrow(Side::Right),
+            KeyCode::Up => self.arrow(Side::Top),
+            KeyCode::Down => self.arrow(Side::Bottom),
+            _ => {}
+        }
+        true
+    }
+
+    fn arrow(&mut self, towards: Side) {
+        match self.grabbed {
+            Some(moves) => self.slide(towards, moves),
+            None => self.select(towards),
+        }
+    }
+
+    /// Slide the grabbed piece a step. The steps taken along the same axis
+    /// since it was grabbed make up a single move, so that they count as
+    /// one, and turning a single tile starts a new move.
+    fn slide(&mut self, towards: Side, moves_before: usize) {
+        let (axis, step) = match towards {
+            Side::Left => (Direction::Horizontal, -1),
+            Side::Right => (Direction::Horizontal, 1),
+            Side::Top => (Direction::Vertical, -1),
+            Side::Bottom => (Direction::Vertical, 1),
+        };
+        let board = self.session.board();
+        if board.make_move(self.selected, axis, step).is_none() {
+            self.message = "The piece can't go there".to_string();
+            return;
+        }
+
+        // the move so far is taken back and played again one step longer
+        let mut steps = step;
+        let history = self.session.history();
+        let same_axis = history.last().is_some_and(|mov| mov.axis() == axis);
+        if history.len() > moves_before && same_axis {
+            if let Some(mov) = self.session.retract() {
+                steps += signed_steps(&mov);
+            }
+        }
+        if steps != 0 {
+            let board = self.session.board();
+            if let Some(mov) = board.make_move(self.selected, axis, steps) {
+                // the move was found legal on this board
+                let _ = self.session.apply(mov);
+            }
+        }
+        self.message.clear();
+
+        if self.session.board().is_won {
+            self.grabbed = None;
+        }
+    }
+
+    /// Select the closest piece towards the side, if there is any
+    fn select(&mut self, towards: Side) {
+        let board = self.session.board();
+        let (x, y) = center(board, self.selected);
+        let closest = (0..board.pieces().len())
+            .filter_map(|id| {
+                let (other_x, other_y) = center(board, id);
+                let (along, across) = match towards {
+                    Side::Left => (x - other_x, y - other_y),
+                    Side::Right => (other_x - x, y - other_y),
+                    Side::Top => (y - other_y, x - other_x),
+                    Side::Bottom => (other_y - y, x - other_x),
+                };
+                // pieces off to the side count as further away
+                Some((along + 2 * across.abs(), id)).filter(|_| along > 0)
+            })
+            .min();
+        if let Some((_, id)) = closest {
+            self.selected = id;
+            self.message.clear();
+        }
+    }
+
+    /// Select the piece that an optimal solution moves next and say where
+    fn hint(&mut self) {
+        self.grabbed = None;
+        self.message = match self.session.board().hint() {
+            Hint::Play(mov) => {
+                self.selected = mov.get_piece();
+                let side = match mov {
+                    Move::Left(..) => "left",
+                    Move::Right(..) => "right",
+                    Move::Up(..) => "up",
+                    Move::Down(..) => "down",
+                };
+                format!("Hint: move the selected piece {} by {}", side, mov.steps())
+            }
+            Hint::Won => "The board is already solved".to_string(),
+            Hint::Unsolvable => "The board can't be solved from here, try undoing".to_string(),
+        };
+    }
+}
+
+/// The steps of a move, negative for moves left or up
+fn signed_steps(mov: &Move) -> i32 {
+    let steps = mov.steps() as i32;
+    match mov {
+        Move::Left(..) | Move::Up(..) => -steps,
+        Move::Right(..) | Move::Down(..) => steps,
+    }
+}
+
+/// The center of a piece, in half tiles so that it is a whole number
+fn center(board: &Board, id: usize) -> (i64, i64) {
+    let tiles: Vec<Tile> = board.piece_tiles(&board.pieces()[id]).collect();
+    let (first, last) = (tiles[0], tiles[tiles.len() - 1]);
+    (
+        i64::from(first.0) + i64::from(last.0),
+        i64::from(first.1) + i64::from(last.1),
+    )
+}
+
+fn draw(out: &mut impl Write, game: &Game) -> io::Result<()> {
+    let board = game.session.board();
+    let moves = game.session.history().len();
+    let status = if board.is_won {
+        format!("Congratulations, you solved it in {} moves!", moves)
+    } else if game.grabbed.is_some() {
+        format!("Moves: {}, sliding the selected piece", moves)
+    } else {
+        format!("Moves: {}", moves)
+    };
+
+    // raw mode doesn't go back to the start of the line on a newline
+    let drawing = board
+        .render_selected(&[game.selected])
+        .replace('\n', "\r\n");
+    execute!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+    write!(
+        out,
+        "{}\r\n\r\n{}\r\n{}\r\n\r\n{}",
+        drawing, status, game.message, HELP
+    )?;
+    out.flush()
+}
+
+/// Gives the terminal back when dropped, so that it is restored after
+/// a panic or an error too
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grab(board: Board) -> Game {
+        let mut game = Game {
+            session: GameSession::new(board),
+            selected: 0,
+            grabbed: None,
+            message: String::new(),
+        };
+        game.handle(KeyEvent::from(KeyCode::Char(' ')));
+        game
+    }
+
+    fn press(game: &mut Game, code: KeyCode) {
+        game.handle(KeyEvent::from(code));
+    }
+
+    /// A single tile in the middle of a board that it can't win
+    fn single_tile() -> Board {
+        let pieces = vec![Piece::marked((2, 2), 1, Direction::Horizontal)];
+        Board::new(5, 5, (0, 0), pieces)
+    }
+
+    #[test]
+    fn steps_along_an_axis_make_one_move() {
+        let mut game = grab(single_tile());
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Left);
+        assert_eq!(game.session.history(), &[Move::Right(0, 1)]);
+    }
+
+    #[test]
+    fn turning_starts_a_new_move() {
+        let mut game = grab(single_tile());
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Up);
+        assert_eq!(game.session.history(), &[Move::Right(0, 1), Move::Up(0, 1)]);
+
+        let mut game = grab(single_tile());
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Up);
+        assert_eq!(game.session.history(), &[Move::Right(0, 2), Move::Up(0, 1)]);
+        assert_eq!(game.session.board().pieces()[0].location(), (4, 1));
+    }
+
+    #[test]
+    fn sliding_back_takes_the_move_back() {
+        let mut game = grab(single_tile());
+        press(&mut game, KeyCode::Down);
+        press(&mut game, KeyCode::Up);
+        assert!(game.session.history().is_empty());
+        // the move that was taken back isn't redone
+        press(&mut game, KeyCode::Char('r'));
+        assert!(game.session.history().is_empty());
+    }
+}
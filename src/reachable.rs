@@ -0,0 +1,97 @@
+use crate::board::*;
+use crate::solver::BoardHasher;
+use std::collections::{HashSet, VecDeque};
+
+/// An iterator over every board reachable from a start board,
+/// in breadth first order, see `Board::reachable`
+#[derive(Debug, Clone)]
+pub struct ReachableIter {
+    seen: HashSet<Board, BoardHasher>,
+    queue: VecDeque<Board>,
+}
+
+impl Board {
+    /// Lazily walk every distinct board that can be reached from this one,
+    /// starting with the board itself and continuing in breadth first order
+    pub fn reachable(self) -> ReachableIter {
+        let mut seen = HashSet::default();
+        seen.insert(self.clone());
+        let mut queue = VecDeque::new();
+        queue.push_back(self);
+
+        ReachableIter { seen, queue }
+    }
+
+    /// An upper bound on the number of boards that `reachable` walks,
+    /// found without searching. Every piece can at most be anywhere in
+    /// the run of tiles without walls along its axis that it is in, so
+    /// the bound is the product of the number of such places. It saturates
+    /// at `usize::MAX`, use `reachable().count()` for the exact number.
+    pub fn estimate_states(&self) -> usize {
+        let walls = Board::occupied_tiles(self.width, self.height, &[], &self.walls);
+        let tiles = (self.width * self.height) as usize - walls.len();
+        self.pieces().iter().fold(1, |states: usize, piece| {
+            // a piece of a single tile can be on any tile without a wall
+            if piece.size() == 1 {
+                return states.saturating_mul(tiles);
+            }
+            let (x, y) = piece.location();
+            // the tiles of the row or column of the piece,
+            // and where the piece starts and ends in it
+            let (line, start): (Vec<Tile>, u32) = match piece.direction() {
+                Direction::Horizontal => ((0..self.width).map(|i| (i, y)).collect(), x),
+                Direction::Vertical => ((0..self.height).map(|i| (x, i)).collect(), y),
+            };
+            // a piece can be anywhere along its line if it wraps around
+            if !self.topology().is_rectangular() {
+                return states.saturating_mul(line.len());
+            }
+            let end = (start + piece.size()).min(line.len() as u32);
+
+            let free = |t: &&Tile| !walls.contains(t);
+            let before = line[..start as usize].iter().rev().take_while(free).count();
+            let after = line[end as usize..].iter().take_while(free).count();
+
+            states.saturating_mul(before + after + 1)
+        })
+    }
+}
+
+impl Iterator for ReachableIter {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        let board = self.queue.pop_front()?;
+        for (future, _) in board.future_boards() {
+            if !self.seen.contains(&future) {
+                self.seen.insert(future.clone());
+                self.queue.push_back(future);
+            }
+        }
+        Some(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reachable_states_of_the_sample() {
+        let board = Board::sample();
+        let mut reachable = board.clone().reachable();
+        assert_eq!(reachable.next(), Some(board.clone()));
+        assert_eq!(1 + reachable.count(), 4643);
+        assert!(board.estimate_states() >= 4643);
+    }
+
+    #[test]
+    fn reachable_stops_when_exhausted() {
+        // a single tile can be anywhere on the board
+        let pieces = vec![Piece::marked((0, 1), 1, Direction::Horizontal)];
+        let board = Board::new(3, 3, (2, 2), pieces);
+        let boards: Vec<Board> = board.reachable().collect();
+        assert_eq!(boards.len(), 9);
+        assert!(boards.iter().any(|b| b.is_won));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::board::*;
+use owo_colors::{AnsiColors, OwoColorize};
+use std::fmt::Write;
+
+const PIECE_COLORS: [AnsiColors; 6] = [
+    AnsiColors::Blue,
+    AnsiColors::Green,
+    AnsiColors::Yellow,
+    AnsiColors::Magenta,
+    AnsiColors::Cyan,
+    AnsiColors::BrightBlue,
+];
+
+impl Board {
+    /// Draw the board like `Display` does, with ANSI colors for a terminal.
+    /// Every piece gets a color of its own, the marked pieces are bold red
+    /// and the goals that nothing covers are bold green.
+    pub fn render_colored(&self) -> String {
+        self.render_highlighted(&[])
+    }
+
+    /// Draw the board like `render_colored` with the pieces that moved since
+    /// the board before underlined and reversed, see `Board::diff`.
+    pub fn render_diff(&self, before: &Board) -> String {
+        let moved: Vec<Tile> = before.diff(self).into_iter().map(|(_, to)| to).collect();
+        self.render_highlighted(&moved)
+    }
+
+    /// Draw the board like `render_colored` with the pieces
+    /// with the given ids underlined and reversed
+    pub fn render_selected(&self, pieces: &[usize]) -> String {
+        let selected: Vec<Tile> = pieces
+            .iter()
+            .filter_map(|&id| self.pieces().get(id))
+            .flat_map(|p| self.piece_tiles(p))
+            .collect();
+        self.render_highlighted(&selected)
+    }
+
+    /// Draw the board in color with the given tiles highlighted
+    fn render_highlighted(&self, highlighted: &[Tile]) -> String {
+        let letters: Vec<Vec<char>> = self
+            .to_string()
+            .lines()
+            .map(|row| row.chars().collect())
+            .collect();
+
+        let mut out = String::new();
+        for y in 0..self.height {
+            if y > 0 {
+                out.push('\n');
+            }
+            for x in 0..self.width {
+                let c = letters[y as usize][x as usize];
+                let piece = self.piece_at((x, y)).map(|id| &self.pieces()[id]);
+                // writing to a string can't fail
+                let _ = match piece {
+                    Some(p) if highlighted.contains(&(x, y)) => {
+                        let color = if p.is_marked() {
+                            AnsiColors::Red
+                        } else {
+                            PIECE_COLORS[p.id() % PIECE_COLORS.len()]
+                        };
+                        write!(out, "{}", c.color(color).bold().underline().reversed())
+                    }
+                    Some(p) if p.is_marked() => write!(out, "{}", c.red().bold()),
+                    Some(p) => {
+                        let color = PIECE_COLORS[p.id() % PIECE_COLORS.len()];
+                        write!(out, "{}", c.color(color))
+                    }
+                    None if c == '+' => write!(out, "{}", c.green().bold()),
+                    None if c == '#' => write!(out, "{}", c.bright_black()),
+                    None => write!(out, "{}", c.dimmed()),
+                };
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Leave out the ANSI escape codes of a drawing
+    fn strip_colors(drawing: &str) -> String {
+        let mut plain = String::new();
+        let mut escaped = false;
+        for c in drawing.chars() {
+            match c {
+                '\x1b' => escaped = true,
+                'm' if escaped => escaped = false,
+                _ if escaped => {}
+                c => plain.push(c),
+            }
+        }
+        plain
+    }
+
+    #[test]
+    fn render_diff_highlights_the_moved_piece() {
+        let board = Board::sample();
+        let moved = board.play(&Move::Up(5, 1));
+        let drawing = moved.render_diff(&board);
+        assert_eq!(strip_colors(&drawing), moved.to_string());
+        // the three tiles of `f` are reversed, and nothing else
+        assert_eq!(drawing.matches("\x1b[7m").count(), 3);
+        assert_eq!(moved.render_diff(&moved).matches("\x1b[7m").count(), 0);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::board::*;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A board being played by hand, with a history of the moves
+/// so that they can be undone and redone
+#[derive(Debug, Clone)]
+pub struct GameSession {
+    board: Board,
+    history: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+impl GameSession {
+    /// Start a session from the given board
+    pub fn new(board: Board) -> Self {
+        GameSession {
+            board,
+            history: vec![],
+            undone: vec![],
+        }
+    }
+
+    /// Get the current board
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Get the moves played so far, oldest first
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Play a move if it is legal. The moves that were undone
+    /// can't be redone after a new move.
+    pub fn apply(&mut self, mov: Move) -> Result<(), MoveError> {
+        self.board = self.board.try_play(&mov)?;
+        self.history.push(mov);
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Take back the last move and return it,
+    /// or `None` if no moves have been played
+    pub fn undo(&mut self) -> Option<Move> {
+        let mov = self.history.pop()?;
+        self.board = self.board.undo(&mov);
+        self.undone.push(mov);
+        Some(mov)
+    }
+
+    /// Take back the last move like `undo`, but without keeping it
+    /// to be redone, for changing a move rather than undoing it
+    pub fn retract(&mut self) -> Option<Move> {
+        let mov = self.history.pop()?;
+        self.board = self.board.undo(&mov);
+        Some(mov)
+    }
+
+    /// Play the last undone move again and return it,
+    /// or `None` if there is nothing to redo
+    pub fn redo(&mut self) -> Option<Move> {
+        let mov = self.undone.pop()?;
+        self.board = self.board.play(&mov);
+        self.history.push(mov);
+        Some(mov)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_undo_redo() {
+        let start = Board::sample();
+        let mut session = GameSession::new(start.clone());
+        session.apply(Move::Right(11, 2)).unwrap();
+        session.apply(Move::Up(5, 1)).unwrap();
+        let after_two = session.board().clone();
+        assert_eq!(session.apply(Move::Right(3, 1)), Err(MoveError::Blocked));
+        assert_eq!(session.history().len(), 2);
+
+        assert_eq!(session.undo(), Some(Move::Up(5, 1)));
+        assert_eq!(session.undo(), Some(Move::Right(11, 2)));
+        assert_eq!(session.undo(), None);
+        assert_eq!(session.board(), &start);
+
+        assert_eq!(session.redo(), Some(Move::Right(11, 2)));
+        assert_eq!(session.redo(), Some(Move::Up(5, 1)));
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.board(), &after_two);
+    }
+
+    #[test]
+    fn a_new_move_clears_the_redo_stack() {
+        let mut session = GameSession::new(Board::sample());
+        session.apply(Move::Right(11, 2)).unwrap();
+        session.undo();
+        session.apply(Move::Up(5, 1)).unwrap();
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.history(), &[Move::Up(5, 1)]);
+
+        // a retracted move can't be redone either
+        assert_eq!(session.retract(), Some(Move::Up(5, 1)));
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.board(), &Board::sample());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::board::*;
+use crate::solver::{backtrack, Visited};
+use std::collections::VecDeque;
+
+/// An iterator over the solutions of a board, shortest first,
+/// see `Board::solutions`
+#[derive(Debug, Clone)]
+pub struct Solutions {
+    visited: Visited,
+    queue: VecDeque<Board>,
+}
+
+impl Board {
+    /// Lazily find the solutions of this board in order of increasing
+    /// length, so the first one is optimal. There is one solution for every
+    /// won board that can be reached without passing another won board,
+    /// which leaves out solutions that only play the same moves in another
+    /// order, as those end on the same board.
+    pub fn solutions(self) -> Solutions {
+        let mut visited = Visited::default();
+        visited.insert(self.clone(), None);
+        let mut queue = VecDeque::new();
+        queue.push_back(self);
+
+        Solutions { visited, queue }
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<Move>;
+
+    fn next(&mut self) -> Option<Vec<Move>> {
+        while let Some(board) = self.queue.pop_front() {
+            // a solution ends on the first won board it reaches
+            if board.is_won {
+                return Some(backtrack(board, &self.visited));
+            }
+
+            for (future, mov) in board.future_boards() {
+                if !self.visited.contains_key(&future) {
+                    self.visited.insert(future.clone(), Some(mov));
+                    self.queue.push_back(future);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortest_solutions_come_first() {
+        let board = Board::sample();
+        let optimal = crate::solve_path(board.clone()).unwrap().len();
+        let solutions: Vec<Vec<Move>> = board.clone().solutions().take(3).collect();
+        assert_eq!(solutions.len(), 3);
+        assert_eq!(solutions[0].len(), optimal);
+        assert!(solutions.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+
+        // every solution ends on a board of its own
+        let ends: Vec<Board> = solutions
+            .iter()
+            .map(|moves| board.replay(moves).unwrap())
+            .collect();
+        assert!(ends.iter().all(|end| end.is_won));
+        assert!(ends[0] != ends[1] && ends[1] != ends[2] && ends[0] != ends[2]);
+    }
+
+    #[test]
+    fn won_and_unsolvable_boards() {
+        let board = Board::sample();
+        let won = board.replay(&crate::solve_path(board.clone()).unwrap()).unwrap();
+        assert_eq!(won.solutions().next(), Some(vec![]));
+
+        let pieces = vec![
+            Piece::marked((0, 0), 2, Direction::Horizontal),
+            Piece::new((2, 0), 1, Direction::Vertical).with_frozen(true),
+        ];
+        assert_eq!(Board::new(4, 1, (3, 0), pieces).solutions().next(), None);
+    }
+}
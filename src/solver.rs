@@ -0,0 +1,1332 @@
+use crate::bitboard::BitBoard;
+use crate::board::*;
+use crate::heuristic::{BlockingCount, Heuristic};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The hasher used for the tables of the solvers. Boards don't need
+/// protection against DoS attacks, so with the `fxhash` feature the
+/// much faster Fx hasher is used instead of SipHash.
+#[cfg(feature = "fxhash")]
+pub type BoardHasher = rustc_hash::FxBuildHasher;
+#[cfg(not(feature = "fxhash"))]
+pub type BoardHasher = std::collections::hash_map::RandomState;
+
+/// A transposition table mapping every visited board
+/// to the move that reached it
+pub type Visited = HashMap<Board, Option<Move>, BoardHasher>;
+
+/// How many boards of a wave the BFS expands between checking its budget
+const CHECK_INTERVAL: usize = 1024;
+
+/// Reasons that a solver didn't return a solution
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum SolveError {
+    /// No winning configuration can be reached
+    Unsolvable,
+    /// The time ran out before a solution was found
+    Timeout { states_explored: usize },
+    /// The table of visited boards grew to its limit before a solution was found
+    StateLimitExceeded { states_explored: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Unsolvable => write!(f, "the board has no solution"),
+            SolveError::Timeout { states_explored } => write!(
+                f,
+                "the solver timed out after exploring {} states",
+                states_explored
+            ),
+            SolveError::StateLimitExceeded { states_explored } => write!(
+                f,
+                "the solver reached its limit after exploring {} states",
+                states_explored
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Numbers describing how much work a search did
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct SearchStats {
+    /// The number of distinct boards that were visited
+    pub states_explored: usize,
+    /// The largest number of boards in a single wave of the search
+    pub max_frontier: usize,
+    /// The number of moves in the solution, or how deep the
+    /// search went if no solution was found
+    pub depth: u32,
+    /// The wall-clock time the search took
+    pub elapsed: Duration,
+    /// The number of moves in the solution, however far each one slides,
+    /// `0` if no solution was found
+    pub slide_count: u32,
+    /// The number of tiles that the pieces travel in the solution, the sum
+    /// of the steps of its moves, `0` if no solution was found
+    pub cell_count: u32,
+}
+
+/// Limits on how long a search may run
+#[derive(Default)]
+struct Budget {
+    deadline: Option<Instant>,
+    max_states: Option<usize>,
+}
+
+impl Budget {
+    /// Check that the search may go on after exploring the given number of states
+    fn check(&self, states_explored: usize) -> Result<(), SolveError> {
+        let timed_out = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        let full = self.max_states.is_some_and(|max_states| states_explored >= max_states);
+        if timed_out {
+            Err(SolveError::Timeout { states_explored })
+        } else if full {
+            Err(SolveError::StateLimitExceeded { states_explored })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Solve a given board and return the number of steps and the final board,
+/// or `None` if no winning configuration can be reached.
+/// The number of steps is the number of moves in the solution, a board
+/// that is already won is returned right away with zero steps.
+pub fn solve<S: BuildHasher>(
+    start: Board,
+    visited: &mut HashMap<Board, Option<Move>, S>,
+) -> Option<(Board, u32)> {
+    let mut stats = SearchStats::default();
+    let budget = Budget::default();
+    bfs(start, visited, Board::all_moves, &budget, &mut stats, &mut |_| {}, None).ok()
+}
+
+/// Solve a given board like `solve` and return the moves of an optimal
+/// solution in the order they are played, or `None` if it can't be solved
+pub fn solve_path(start: Board) -> Option<Vec<Move>> {
+    let mut visited = Visited::default();
+    let (end, _) = solve(start, &mut visited)?;
+    Some(backtrack(end, &visited))
+}
+
+/// The advice of `Board::hint`
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Hint {
+    /// The first move of an optimal solution
+    Play(Move),
+    /// The board is already won, so there is nothing to play
+    Won,
+    /// No winning configuration can be reached
+    Unsolvable,
+}
+
+impl Board {
+    /// Find the first move of an optimal solution from this board
+    pub fn hint(&self) -> Hint {
+        match solve_path(self.clone()) {
+            Some(moves) => moves.first().map_or(Hint::Won, |&mov| Hint::Play(mov)),
+            None => Hint::Unsolvable,
+        }
+    }
+}
+
+/// Solve a given board like `solve` and return the moves of an optimal
+/// solution, or `None` if it can't be solved, together with statistics
+/// about the search
+pub fn solve_with_stats(start: Board) -> (Option<Vec<Move>>, SearchStats) {
+    let now = Instant::now();
+    let mut visited = Visited::default();
+    let mut stats = SearchStats::default();
+    let budget = Budget::default();
+    let moves = Board::all_moves;
+    let solution = bfs(start, &mut visited, moves, &budget, &mut stats, &mut |_| {}, None)
+        .ok()
+        .map(|(end, _)| backtrack(end, &visited));
+    stats.elapsed = now.elapsed();
+    if let Some(moves) = &solution {
+        stats.slide_count = moves.len() as u32;
+        stats.cell_count = moves.iter().map(Move::steps).sum();
+    }
+    (solution, stats)
+}
+
+/// Solve a given board like `solve_with_stats`, calling `on_progress` with
+/// the statistics so far after every wave of the search, that is every time
+/// the search goes a move deeper. The callback can't stop the search, see
+/// `solve_with_timeout` and `solve_with_state_limit` for that.
+pub fn solve_with_progress(
+    start: Board,
+    mut on_progress: impl FnMut(&SearchStats),
+) -> Option<Vec<Move>> {
+    let mut visited = Visited::default();
+    let mut stats = SearchStats::default();
+    let budget = Budget::default();
+    bfs(start, &mut visited, Board::all_moves, &budget, &mut stats, &mut on_progress, None)
+        .ok()
+        .map(|(end, _)| backtrack(end, &visited))
+}
+
+/// Solve a given board like `solve`, but give up with `SolveError::Timeout`
+/// once the timeout has passed. The time is checked every thousand or so
+/// expanded boards, so the solver may run slightly longer than the timeout.
+pub fn solve_with_timeout(start: Board, timeout: Duration) -> Result<(Board, u32), SolveError> {
+    let budget = Budget {
+        deadline: Some(Instant::now() + timeout),
+        ..Budget::default()
+    };
+    let mut stats = SearchStats::default();
+    bfs(start, &mut Visited::default(), Board::all_moves, &budget, &mut stats, &mut |_| {}, None)
+}
+
+/// Solve a given board like `solve`, but give up with
+/// `SolveError::StateLimitExceeded` once the table of visited boards has
+/// `max_states` boards. The table is checked every thousand or so expanded
+/// boards, and may grow past the limit by the boards one move further.
+pub fn solve_with_state_limit(start: Board, max_states: usize) -> Result<(Board, u32), SolveError> {
+    let budget = Budget {
+        max_states: Some(max_states),
+        ..Budget::default()
+    };
+    let mut stats = SearchStats::default();
+    bfs(start, &mut Visited::default(), Board::all_moves, &budget, &mut stats, &mut |_| {}, None)
+}
+
+/// A table of the legal moves of boards, so that solving boards that share
+/// many positions, such as every board along a game, doesn't generate the
+/// same moves again. It trades memory for time, a search that is only run
+/// once gains nothing as it expands every board once. See
+/// `solve_with_cache`.
+#[derive(Debug, Clone, Default)]
+pub struct MoveCache {
+    moves: HashMap<Board, Vec<Move>, BoardHasher>,
+    hits: usize,
+}
+
+impl MoveCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        MoveCache::default()
+    }
+
+    /// Get the moves of a board like `Board::all_moves`,
+    /// from the cache if they have been generated before
+    pub fn moves(&mut self, board: &Board) -> Vec<Move> {
+        if let Some(moves) = self.moves.get(board) {
+            self.hits += 1;
+            return moves.clone();
+        }
+        let moves = board.all_moves();
+        self.moves.insert(board.clone(), moves.clone());
+        moves
+    }
+
+    /// Get the number of times moves were found in the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the number of boards in the cache
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Remove every board from the cache
+    pub fn clear(&mut self) {
+        self.moves.clear();
+        self.hits = 0;
+    }
+}
+
+/// Solve a given board like `solve_path`, looking up the moves of the
+/// boards in the cache, and adding the ones that are missing
+pub fn solve_with_cache(start: Board, cache: &mut MoveCache) -> Option<Vec<Move>> {
+    let mut visited = Visited::default();
+    let mut stats = SearchStats::default();
+    let budget = Budget::default();
+    let moves = Board::all_moves;
+    bfs(start, &mut visited, moves, &budget, &mut stats, &mut |_| {}, Some(cache))
+        .ok()
+        .map(|(end, _)| backtrack(end, &visited))
+}
+
+/// Solve a given board like `solve`, giving up once either the timeout has
+/// passed or the table of visited boards is full, like `solve_with_timeout`
+/// and `solve_with_state_limit`
+pub(crate) fn solve_with_limits(
+    start: Board,
+    timeout: Duration,
+    max_states: usize,
+) -> Result<(Board, u32), SolveError> {
+    let budget = Budget {
+        deadline: Some(Instant::now() + timeout),
+        max_states: Some(max_states),
+    };
+    let mut stats = SearchStats::default();
+    bfs(start, &mut Visited::default(), Board::all_moves, &budget, &mut stats, &mut |_| {}, None)
+}
+
+/// Solve a given board like `solve`, but only move pieces a single step
+/// at a time. The number of steps is then the number of tiles that the
+/// pieces travel rather than the number of slides.
+pub fn solve_single_steps<S: BuildHasher>(
+    start: Board,
+    visited: &mut HashMap<Board, Option<Move>, S>,
+) -> Option<(Board, u32)> {
+    let mut stats = SearchStats::default();
+    let budget = Budget::default();
+    bfs(start, visited, Board::all_single_moves, &budget, &mut stats, &mut |_| {}, None).ok()
+}
+
+/// Solve a given board like `solve_path`, but only make moves of at most
+/// `max_steps` steps, see `Board::all_moves_limited`. The solution has
+/// the fewest moves of that length, with a single step it is the moves of
+/// `solve_single_steps`. Returns `None` if the board can't be solved.
+pub fn solve_limited(start: Board, max_steps: u32) -> Option<Vec<Move>> {
+    let mut visited = Visited::default();
+    let mut stats = SearchStats::default();
+    let budget = Budget::default();
+    let moves = move |board: &Board| board.all_moves_limited(max_steps);
+    bfs(start, &mut visited, moves, &budget, &mut stats, &mut |_| {}, None)
+        .ok()
+        .map(|(end, _)| backtrack(end, &visited))
+}
+
+/// A function that finds the moves of a board for the BFS, such as
+/// `Board::all_moves`, which is copied to every thread that expands
+/// boards with the `parallel` feature.
+trait MoveGenerator: Fn(&Board) -> Vec<Move> + Sync + Copy {}
+
+impl<F: Fn(&Board) -> Vec<Move> + Sync + Copy> MoveGenerator for F {}
+
+/// Breadth first search from the start board, using the given
+/// function to find the moves of a board and stopping if the budget runs out.
+/// The stats are filled in and passed to `progress` after every wave.
+/// With a cache the moves are looked up in it instead, and the boards
+/// are expanded on a single thread.
+fn bfs<S: BuildHasher>(
+    start: Board,
+    visited: &mut HashMap<Board, Option<Move>, S>,
+    moves: impl MoveGenerator,
+    budget: &Budget,
+    stats: &mut SearchStats,
+    progress: &mut dyn FnMut(&SearchStats),
+    mut cache: Option<&mut MoveCache>,
+) -> Result<(Board, u32), SolveError> {
+    let started = Instant::now();
+    stats.states_explored = 1;
+    stats.max_frontier = 1;
+    // a board that is already won is solved without any moves,
+    // otherwise the first wave would count a move that isn't needed
+    if start.is_won {
+        visited.insert(start.clone(), None);
+        return Ok((start, 0));
+    }
+    if start.quick_unsolvable() {
+        visited.insert(start, None);
+        return Err(SolveError::Unsolvable);
+    }
+
+    let mut boards = future_boards(&start, moves);
+    visited.insert(start, None);
+    // the number of moves made to reach the current wave of boards
+    let mut steps = 0;
+    loop {
+        // remove the board configurations that we already have visited
+        // and add the new ones to our transposition table
+        boards.retain(|(board, mov)| {
+            if visited.contains_key(board) {
+                false
+            } else {
+                visited.insert(board.to_owned(), Some(*mov));
+                true
+            }
+        });
+
+        steps += 1;
+        stats.states_explored = visited.len();
+        stats.max_frontier = stats.max_frontier.max(boards.len());
+        if !boards.is_empty() {
+            stats.depth = steps;
+        }
+        stats.elapsed = started.elapsed();
+        progress(stats);
+
+        if let Some(i) = boards.iter().position(|(board, _)| board.is_won) {
+            return Ok((boards.swap_remove(i).0, steps));
+        }
+
+        let mut new_boards = vec![];
+        for chunk in boards.chunks(CHECK_INTERVAL) {
+            budget.check(visited.len())?;
+            match cache.as_deref_mut() {
+                Some(cache) => {
+                    for (board, _) in chunk {
+                        let futures = cache.moves(board).into_iter().map(|m| (board.play(&m), m));
+                        new_boards.extend(futures);
+                    }
+                }
+                None => new_boards.append(&mut expand_all(chunk, moves)),
+            }
+        }
+
+        // every reachable configuration has been visited
+        if new_boards.is_empty() {
+            return Err(SolveError::Unsolvable);
+        }
+
+        boards = new_boards;
+    }
+}
+
+/// Get the future boards of a board, using the given moves
+fn future_boards(board: &Board, moves: impl MoveGenerator) -> Vec<(Board, Move)> {
+    moves(board)
+        .into_iter()
+        .map(|next| (board.play(&next), next))
+        .collect()
+}
+
+/// Get the future boards of every board in the frontier
+#[cfg(not(feature = "parallel"))]
+fn expand_all(boards: &[(Board, Move)], moves: impl MoveGenerator) -> Vec<(Board, Move)> {
+    boards
+        .iter()
+        .flat_map(|(board, _)| future_boards(board, moves))
+        .collect()
+}
+
+/// Get the future boards of every board in the frontier,
+/// the boards are expanded in parallel but kept in order
+#[cfg(feature = "parallel")]
+fn expand_all(boards: &[(Board, Move)], moves: impl MoveGenerator) -> Vec<(Board, Move)> {
+    boards
+        .par_iter()
+        .flat_map_iter(|(board, _)| future_boards(board, moves))
+        .collect()
+}
+
+/// Walk back from a board found by `solve` to the start, returning the
+/// moves that lead to it in the order they are played
+pub(crate) fn backtrack<S: BuildHasher>(
+    mut board: Board,
+    visited: &HashMap<Board, Option<Move>, S>,
+) -> Vec<Move> {
+    let mut history = vec![];
+    while let Some(Some(prev_move)) = visited.get(&board) {
+        history.push(*prev_move);
+        board = board.undo(prev_move);
+    }
+    history.reverse();
+    history
+}
+
+/// Solve a given board with a BFS that treats boards with the same
+/// canonical form as the same state, see `Board::canonical`, and return
+/// the moves of an optimal solution or `None` if it can't be solved
+pub fn solve_canonical(start: Board) -> Option<Vec<Move>> {
+    if start.is_won {
+        return Some(vec![]);
+    }
+    if start.quick_unsolvable() {
+        return None;
+    }
+
+    // keyed on canonical forms, the moves are those of the actual boards
+    let mut visited = Visited::default();
+    visited.insert(start.canonical(), None);
+    let mut boards = vec![start];
+
+    while !boards.is_empty() {
+        let mut new_boards = vec![];
+        for board in &boards {
+            for (future, mov) in board.future_boards() {
+                let key = future.canonical();
+                if visited.contains_key(&key) {
+                    continue;
+                }
+                visited.insert(key, Some(mov));
+
+                if future.is_won {
+                    return Some(backtrack_canonical(future, &visited));
+                }
+                new_boards.push(future);
+            }
+        }
+        boards = new_boards;
+    }
+
+    None
+}
+
+/// Like `backtrack`, but for a table keyed on canonical forms
+fn backtrack_canonical(mut board: Board, visited: &Visited) -> Vec<Move> {
+    let mut history = vec![];
+    while let Some(Some(prev_move)) = visited.get(&board.canonical()) {
+        history.push(*prev_move);
+        board = board.undo(prev_move);
+    }
+    history.reverse();
+    history
+}
+
+/// Solve a given bitboard with a BFS like `solve` and return the moves
+/// of an optimal solution, or `None` if it can't be solved
+pub fn solve_bitboard(start: BitBoard) -> Option<Vec<Move>> {
+    if start.is_won() {
+        return Some(vec![]);
+    }
+    if start.to_board().quick_unsolvable() {
+        return None;
+    }
+
+    let mut visited: HashMap<BitBoard, Option<Move>, BoardHasher> = HashMap::default();
+    visited.insert(start.clone(), None);
+    let mut boards = vec![start];
+
+    while !boards.is_empty() {
+        let mut new_boards = vec![];
+        for board in &boards {
+            for mov in board.all_moves() {
+                let future = board.play(&mov);
+                if visited.contains_key(&future) {
+                    continue;
+                }
+                visited.insert(future.clone(), Some(mov));
+
+                if future.is_won() {
+                    return Some(backtrack_bitboard(future, &visited));
+                }
+                new_boards.push(future);
+            }
+        }
+        boards = new_boards;
+    }
+
+    None
+}
+
+/// Like `backtrack`, but for a table of bitboards
+fn backtrack_bitboard(
+    mut board: BitBoard,
+    visited: &HashMap<BitBoard, Option<Move>, BoardHasher>,
+) -> Vec<Move> {
+    let mut history = vec![];
+    while let Some(Some(prev_move)) = visited.get(&board) {
+        history.push(*prev_move);
+        board = board.play(&prev_move.reverse());
+    }
+    history.reverse();
+    history
+}
+
+/// Solve a given board with a beam search that only keeps the `beam_width`
+/// boards with the fewest moves left by `Board::min_moves_left` at each
+/// depth. The moves of the first solution found are returned, which isn't
+/// necessarily optimal, or `None` if the beam runs out of new boards.
+pub fn beam_search(start: Board, beam_width: usize) -> Option<Vec<Move>> {
+    if start.is_won {
+        return Some(vec![]);
+    }
+    if start.quick_unsolvable() {
+        return None;
+    }
+
+    let mut visited = Visited::default();
+    visited.insert(start.clone(), None);
+    let mut beam = vec![start];
+
+    while !beam.is_empty() {
+        let mut candidates = vec![];
+        for board in &beam {
+            for (future, mov) in board.future_boards() {
+                if visited.contains_key(&future) {
+                    continue;
+                }
+                visited.insert(future.clone(), Some(mov));
+
+                if future.is_won {
+                    return Some(backtrack(future, &visited));
+                }
+                candidates.push(future);
+            }
+        }
+
+        // the sort is stable, so ties are kept in the order they were found
+        candidates.sort_by_key(Board::min_moves_left);
+        candidates.truncate(beam_width);
+        beam = candidates;
+    }
+
+    None
+}
+
+/// The maximum number of boards that `ida_star` remembers
+/// the depth of during one iteration
+const IDA_TABLE_SIZE: usize = 1 << 16;
+
+/// The outcome of searching below a board in one IDA* iteration
+enum Search {
+    Found,
+    /// The smallest estimate that went over the threshold
+    Exceeded(u32),
+    /// Nothing below the board can lead to a win
+    Exhausted,
+}
+
+/// Solve a given board with iterative deepening A* and return the moves
+/// of an optimal solution, or `None` if no winning configuration can be
+/// reached. Instead of every visited board only the current path and a
+/// table of bounded size are kept in memory. The threshold only grows
+/// while some path was cut off by it, so the search ends once every
+/// path without repeated boards has been explored, and a board that
+/// `quick_unsolvable` rules out isn't searched at all.
+pub fn ida_star(start: Board) -> Option<Vec<Move>> {
+    ida_star_with(start, &BlockingCount).0
+}
+
+/// Solve a given board like `ida_star`, estimating the moves left with the
+/// given heuristic, and return the statistics of the search along with the
+/// moves. The solution is only optimal if the heuristic is admissible. The
+/// boards close to the start are searched again in every iteration, and
+/// count towards `states_explored` every time, and `max_frontier` is the
+/// number of boards on the longest path searched.
+pub fn ida_star_with(start: Board, heuristic: &impl Heuristic) -> (Option<Vec<Move>>, SearchStats) {
+    let now = Instant::now();
+    let mut stats = SearchStats::default();
+    let solution = iterative_deepening(start, heuristic, &mut stats);
+    stats.elapsed = now.elapsed();
+    if let Some(moves) = &solution {
+        stats.depth = moves.len() as u32;
+        stats.slide_count = moves.len() as u32;
+        stats.cell_count = moves.iter().map(Move::steps).sum();
+    }
+    (solution, stats)
+}
+
+/// Solve a given board with iterative deepening depth first search, like
+/// `ida_star` but without estimating the number of moves left, so that the
+/// limit on the number of moves grows by one after every search. The
+/// solution is optimal and little memory is used, at the cost of searching
+/// the boards close to the start again and again.
+pub fn iddfs_solve(start: Board) -> Option<Vec<Move>> {
+    iterative_deepening(start, &|_: &Board| 0, &mut SearchStats::default())
+}
+
+/// Search deeper and deeper until a solution is found, cutting off paths
+/// once the moves made and the estimated moves left exceed the threshold.
+/// The estimate must never be more than the actual number of moves left.
+fn iterative_deepening(
+    start: Board,
+    heuristic: &impl Heuristic,
+    stats: &mut SearchStats,
+) -> Option<Vec<Move>> {
+    if start.quick_unsolvable() {
+        return None;
+    }
+    let mut threshold = heuristic.estimate(&start);
+    let mut tables = IdaTables::default();
+
+    loop {
+        tables.seen.clear();
+        stats.depth = threshold;
+        match ida_search(&start, 0, threshold, heuristic, &mut tables, stats) {
+            Search::Found => return Some(tables.path),
+            Search::Exceeded(next) => threshold = next,
+            Search::Exhausted => return None,
+        }
+    }
+}
+
+/// What an IDA* search keeps in memory besides the stack
+#[derive(Default)]
+struct IdaTables {
+    /// The moves from the start to the board being searched
+    path: Vec<Move>,
+    /// The boards along the path, which are never searched again below
+    /// themselves, so that a cycle can't raise the threshold forever
+    on_path: HashSet<Board, BoardHasher>,
+    /// The smallest depth each board was seen at in the current iteration
+    seen: HashMap<Board, u32, BoardHasher>,
+}
+
+fn ida_search(
+    board: &Board,
+    depth: u32,
+    threshold: u32,
+    heuristic: &impl Heuristic,
+    tables: &mut IdaTables,
+    stats: &mut SearchStats,
+) -> Search {
+    let total = depth + heuristic.estimate(board);
+    if total > threshold {
+        return Search::Exceeded(total);
+    }
+    if board.is_won {
+        return Search::Found;
+    }
+    if tables.on_path.contains(board) {
+        return Search::Exhausted;
+    }
+
+    // a board that was already reached with fewer moves has
+    // been searched with a larger budget than it has now
+    match tables.seen.get(board) {
+        Some(&d) if d <= depth => return Search::Exhausted,
+        Some(_) => {
+            tables.seen.insert(board.to_owned(), depth);
+        }
+        None if tables.seen.len() < IDA_TABLE_SIZE => {
+            tables.seen.insert(board.to_owned(), depth);
+        }
+        None => {}
+    }
+    stats.states_explored += 1;
+    stats.max_frontier = stats.max_frontier.max(depth as usize + 1);
+
+    tables.on_path.insert(board.to_owned());
+    let mut next = None;
+    for (future, mov) in board.future_boards() {
+        tables.path.push(mov);
+        match ida_search(&future, depth + 1, threshold, heuristic, tables, stats) {
+            Search::Found => return Search::Found,
+            Search::Exceeded(e) => next = Some(next.map_or(e, |n: u32| n.min(e))),
+            Search::Exhausted => {}
+        }
+        tables.path.pop();
+    }
+    tables.on_path.remove(board);
+
+    next.map_or(Search::Exhausted, Search::Exceeded)
+}
+
+/// Solve a given board with a depth first search and return the moves of
+/// the first solution found with at most `max_depth` moves, or `None` if
+/// there is none. The solution isn't necessarily optimal, but only the
+/// current path and a table of bounded size are kept in memory.
+pub fn dfs_solve(start: Board, max_depth: u32) -> Option<Vec<Move>> {
+    if start.quick_unsolvable() {
+        return None;
+    }
+    let mut path = vec![];
+    let mut on_path = HashSet::default();
+    // the smallest depth each board was seen at
+    let mut seen = HashMap::default();
+
+    if dfs_search(&start, max_depth, &mut path, &mut on_path, &mut seen) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+fn dfs_search(
+    board: &Board,
+    max_depth: u32,
+    path: &mut Vec<Move>,
+    on_path: &mut HashSet<Board, BoardHasher>,
+    seen: &mut HashMap<Board, u32, BoardHasher>,
+) -> bool {
+    if board.is_won {
+        return true;
+    }
+    let depth = path.len() as u32;
+    if depth + board.min_moves_left() > max_depth || on_path.contains(board) {
+        return false;
+    }
+
+    // a board that was already reached with fewer moves has
+    // been searched with a larger budget than it has now
+    match seen.get(board) {
+        Some(&d) if d <= depth => return false,
+        Some(_) => {
+            seen.insert(board.to_owned(), depth);
+        }
+        None if seen.len() < IDA_TABLE_SIZE => {
+            seen.insert(board.to_owned(), depth);
+        }
+        None => {}
+    }
+
+    on_path.insert(board.to_owned());
+    for (future, mov) in board.future_boards() {
+        path.push(mov);
+        if dfs_search(&future, max_depth, path, on_path, seen) {
+            return true;
+        }
+        path.pop();
+    }
+    on_path.remove(board);
+
+    false
+}
+
+/// The move that leads to each board in a search, and how many moves
+/// away from where that search started it is, or how much it costs to
+/// get there in `dijkstra_solve`
+type Parents = HashMap<Board, (Option<Move>, u32), BoardHasher>;
+
+/// Solve a given board by searching forward from it and backward from
+/// every won board at the same time until the searches meet, returning
+/// the moves of an optimal solution or `None` if it can't be solved.
+/// The won boards are every placement of the pieces along their axes
+/// where the marked piece covers the goal.
+pub fn bidirectional_solve(start: Board) -> Option<Vec<Move>> {
+    if start.is_won {
+        return Some(vec![]);
+    }
+    if start.quick_unsolvable() {
+        return None;
+    }
+
+    let mut backward_frontier = start.won_boards();
+    let mut backward: Parents = backward_frontier
+        .iter()
+        .map(|b| (b.to_owned(), (None, 0)))
+        .collect();
+
+    let mut forward = Parents::default();
+    forward.insert(start.clone(), (None, 0));
+    let mut forward_frontier = vec![start];
+
+    loop {
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            return None;
+        }
+
+        // always expand the smaller of the two frontiers
+        let meeting = if forward_frontier.len() <= backward_frontier.len() {
+            expand(&mut forward_frontier, &mut forward, &backward)
+        } else {
+            expand(&mut backward_frontier, &mut backward, &forward)
+        };
+
+        if let Some(board) = meeting {
+            let mut path = walk_back(&board, &forward);
+            // the backward search made its moves from the won board towards
+            // the meeting point, so they are undone in the opposite order
+            let to_goal = walk_back(&board, &backward);
+            path.extend(to_goal.iter().rev().map(Move::reverse));
+            return Some(path);
+        }
+    }
+}
+
+/// Replace the frontier with every unseen board one move away from it.
+/// Returns the new board closest to the start of the other search
+/// if any of them has been seen by it.
+fn expand(frontier: &mut Vec<Board>, visited: &mut Parents, other: &Parents) -> Option<Board> {
+    let mut new_boards = vec![];
+    let mut meeting: Option<(Board, u32)> = None;
+
+    for board in frontier.drain(..) {
+        let depth = visited[&board].1 + 1;
+        for (future, mov) in board.future_boards() {
+            if visited.contains_key(&future) {
+                continue;
+            }
+            visited.insert(future.clone(), (Some(mov), depth));
+
+            if let Some(&(_, other_depth)) = other.get(&future) {
+                if meeting.as_ref().is_none_or(|(_, d)| other_depth < *d) {
+                    meeting = Some((future.clone(), other_depth));
+                }
+            }
+            new_boards.push(future);
+        }
+    }
+
+    *frontier = new_boards;
+    meeting.map(|(board, _)| board)
+}
+
+/// The moves that lead from where a search started to the given board
+fn walk_back(board: &Board, visited: &Parents) -> Vec<Move> {
+    let mut board = board.to_owned();
+    let mut history = vec![];
+    while let Some(&(Some(prev_move), _)) = visited.get(&board) {
+        history.push(prev_move);
+        board = board.undo(&prev_move);
+    }
+    history.reverse();
+    history
+}
+
+/// Solve a given board with Dijkstra's algorithm, minimizing the total
+/// `Move::cost` of the moves rather than their number. Returns the moves
+/// of the cheapest solution and its cost, or `None` if it can't be solved.
+pub fn dijkstra_solve(start: Board) -> Option<(Vec<Move>, u32)> {
+    if start.quick_unsolvable() {
+        return None;
+    }
+    // the cheapest known way to every board, the second value is its cost
+    let mut parents = Parents::default();
+    parents.insert(start.clone(), (None, 0));
+    // boards waiting in the queue, which refers to them by index
+    let mut boards = vec![Some(start)];
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((0, 0)));
+
+    while let Some(Reverse((cost, i))) = queue.pop() {
+        let board = boards[i].take().expect("every board is queued once");
+        // the board was queued again at a lower cost since
+        if parents[&board].1 < cost {
+            continue;
+        }
+        if board.is_won {
+            return Some((walk_back(&board, &parents), cost));
+        }
+
+        for (future, mov) in board.future_boards() {
+            let future_cost = cost + mov.cost(&board);
+            if parents.get(&future).is_some_and(|&(_, c)| c <= future_cost) {
+                continue;
+            }
+            parents.insert(future.clone(), (Some(mov), future_cost));
+            boards.push(Some(future));
+            queue.push(Reverse((future_cost, boards.len() - 1)));
+        }
+    }
+
+    None
+}
+
+/// A board in `solve_min_pieces`, with the id of the piece that moved last
+type PieceState = (Board, Option<usize>);
+
+/// The move that leads to each state in `solve_min_pieces`, with the piece
+/// that moved before it, and how much it costs to get there
+type PieceParents = HashMap<PieceState, (Option<(Move, Option<usize>)>, u32), BoardHasher>;
+
+/// Solve a given board minimizing the number of times a piece is picked up,
+/// rather than the number of moves. Moving the piece that moved last again
+/// is free and moving any other piece costs one, so a solution that moves
+/// one piece five times in a row beats one that moves five pieces once. A
+/// piece that moves again after another one did is counted again. Among
+/// the cheapest solutions the one returned isn't necessarily the one with
+/// the fewest moves.
+/// Returns `None` if the board can't be solved.
+pub fn solve_min_pieces(start: Board) -> Option<Vec<Move>> {
+    if start.quick_unsolvable() {
+        return None;
+    }
+    // the cheapest known way to every state
+    let mut parents = PieceParents::default();
+    let start = (start, None);
+    parents.insert(start.clone(), (None, 0));
+    // a 0-1 BFS, free moves go to the front of the queue
+    // and the others to the back, so it stays sorted by cost
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+
+    while let Some((state, cost)) = queue.pop_front() {
+        // the state was queued again at a lower cost since
+        if parents[&state].1 < cost {
+            continue;
+        }
+        let (board, last) = &state;
+        if board.is_won {
+            let (mut state, mut history) = (state, vec![]);
+            while let Some(&(Some((mov, before)), _)) = parents.get(&state) {
+                history.push(mov);
+                state = (state.0.undo(&mov), before);
+            }
+            history.reverse();
+            return Some(history);
+        }
+
+        for (future, mov) in board.future_boards() {
+            let piece = mov.get_piece();
+            let free = *last == Some(piece);
+            let future_cost = if free { cost } else { cost + 1 };
+            let next = (future, Some(piece));
+            if parents.get(&next).is_some_and(|&(_, c)| c <= future_cost) {
+                continue;
+            }
+            parents.insert(next.clone(), (Some((mov, *last)), future_cost));
+            if free {
+                queue.push_front((next, future_cost));
+            } else {
+                queue.push_back((next, future_cost));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board that `quick_unsolvable` doesn't rule out, where the marked
+    /// piece is stuck behind a column it fills but a single tile can
+    /// still go round in circles
+    fn stuck_board() -> Board {
+        let pieces = vec![
+            Piece::marked((0, 1), 2, Direction::Horizontal),
+            Piece::new((1, 0), 1, Direction::Horizontal),
+            Piece::new((3, 0), 3, Direction::Vertical),
+        ];
+        Board::new(4, 3, (3, 1), pieces)
+    }
+
+    #[test]
+    fn ida_star_matches_bfs() {
+        let optimal = solve_path(Board::sample()).unwrap();
+        let (solution, stats) = ida_star_with(Board::sample(), &BlockingCount);
+        let solution = solution.unwrap();
+        assert_eq!(solution.len(), optimal.len());
+        assert!(Board::sample().replay(&solution).unwrap().is_won);
+        // only the path is kept, besides the bounded table
+        assert!(stats.max_frontier <= solution.len() + 1);
+    }
+
+    #[test]
+    fn canonical_solve_is_optimal() {
+        let solution = solve_canonical(Board::sample()).unwrap();
+        assert_eq!(solution.len(), 50);
+        assert!(Board::sample().replay(&solution).unwrap().is_won);
+        let mirrored = solve_canonical(Board::sample().mirror_horizontal()).unwrap();
+        assert_eq!(mirrored.len(), 50);
+    }
+
+    #[test]
+    fn solvers_give_up_on_a_perpendicular_marked_piece() {
+        // the goal is in the row of the marked piece, which moves up and down
+        let pieces = vec![
+            Piece::marked((0, 0), 2, Direction::Vertical),
+            Piece::new((2, 1), 1, Direction::Horizontal),
+        ];
+        let board = Board::new(4, 4, (3, 0), pieces);
+        assert!(board.quick_unsolvable());
+
+        assert_eq!(solve(board.clone(), &mut Visited::default()), None);
+        assert_eq!(solve_canonical(board.clone()), None);
+        assert_eq!(solve_bitboard(board.to_bitboard().unwrap()), None);
+        assert_eq!(beam_search(board.clone(), 10), None);
+        assert_eq!(ida_star(board.clone()), None);
+        assert_eq!(iddfs_solve(board.clone()), None);
+        assert_eq!(dfs_solve(board.clone(), 20), None);
+        assert_eq!(bidirectional_solve(board.clone()), None);
+        assert_eq!(dijkstra_solve(board.clone()), None);
+        assert_eq!(solve_min_pieces(board), None);
+    }
+
+    #[test]
+    fn ida_star_ends_on_unsolvable_boards() {
+        let board = stuck_board();
+        assert!(!board.quick_unsolvable());
+        assert_eq!(ida_star(board), None);
+
+        // a wall right in front of the marked piece, and a lot of room
+        // for the single tiles to go round in circles
+        let mut pieces = vec![Piece::marked((0, 0), 2, Direction::Horizontal)];
+        pieces.extend((1..8).map(|y| Piece::new((y, y), 1, Direction::Horizontal)));
+        let walled = Board::try_with_walls(8, 8, (7, 0), pieces, vec![(2, 0)]).unwrap();
+        assert_eq!(ida_star(walled), None);
+    }
+
+    #[test]
+    fn blocked_board_has_no_solution() {
+        // the piece in front of the marked one fills its column
+        let pieces = vec![
+            Piece::marked((0, 0), 2, Direction::Horizontal),
+            Piece::new((2, 0), 2, Direction::Vertical),
+        ];
+        let board = Board::new(3, 2, (2, 0), pieces);
+        assert_eq!(solve(board.clone(), &mut Visited::default()), None);
+        assert_eq!(solve_path(board), None);
+    }
+
+    #[test]
+    fn steps_match_the_history() {
+        let mut visited = Visited::default();
+        let (end, steps) = solve(Board::sample(), &mut visited).unwrap();
+        let history = backtrack(end, &visited);
+        assert_eq!(steps as usize, history.len());
+        assert_eq!(steps, 50);
+    }
+
+    #[test]
+    fn bidirectional_path_replays() {
+        let solution = bidirectional_solve(Board::sample()).unwrap();
+        assert!(Board::sample().replay(&solution).unwrap().is_won);
+        assert_eq!(solution.len(), 50);
+        assert_eq!(bidirectional_solve(stuck_board()), None);
+    }
+
+    #[test]
+    fn dfs_finds_solutions_within_the_depth() {
+        let board = Board::sample();
+        let solution = dfs_solve(board.clone(), 60).unwrap();
+        assert!(solution.len() <= 60);
+        assert!(board.replay(&solution).unwrap().is_won);
+
+        // the optimal solution takes 50 moves
+        assert_eq!(dfs_solve(board.clone(), 49), None);
+        assert_eq!(dfs_solve(board.clone(), 50).map(|s| s.len()), Some(50));
+        assert_eq!(dfs_solve(stuck_board(), 100), None);
+    }
+
+    #[test]
+    fn won_board_is_solved_right_away() {
+        let pieces = vec![
+            Piece::marked((2, 1), 2, Direction::Horizontal),
+            Piece::new((0, 0), 2, Direction::Vertical),
+        ];
+        let board = Board::new(4, 3, (3, 1), pieces);
+        assert!(board.is_won);
+
+        let mut visited = Visited::default();
+        assert_eq!(solve(board.clone(), &mut visited), Some((board.clone(), 0)));
+        assert_eq!(visited.len(), 1);
+        assert_eq!(solve_path(board.clone()), Some(vec![]));
+
+        let (solution, stats) = solve_with_stats(board);
+        assert_eq!(solution, Some(vec![]));
+        assert_eq!((stats.states_explored, stats.depth), (1, 0));
+    }
+
+    #[test]
+    fn beam_search_solution_replays() {
+        let board = Board::sample();
+        let solution = beam_search(board.clone(), 1000).unwrap();
+        assert!(board.replay(&solution).unwrap().is_won);
+        assert!(solution.len() >= 50);
+        assert_eq!(beam_search(stuck_board(), 1000), None);
+    }
+
+    #[test]
+    fn dijkstra_minimizes_the_cost() {
+        // the tile can wait for the piece in its way to slide two tiles
+        // down, or go round it for less
+        let pieces = vec![
+            Piece::new((2, 0), 2, Direction::Vertical),
+            Piece::marked((0, 1), 1, Direction::Horizontal),
+        ];
+        let board = Board::new(5, 4, (4, 1), pieces);
+        let cost = |moves: &[Move]| {
+            let mut board = board.clone();
+            moves.iter().fold(0, |total, mov| {
+                let cost = mov.cost(&board);
+                board.play_in_place(mov);
+                total + cost
+            })
+        };
+
+        let fewest_moves = solve_path(board.clone()).unwrap();
+        assert_eq!(fewest_moves.len(), 2);
+        assert_eq!(cost(&fewest_moves), 8);
+
+        let (cheapest, total) = dijkstra_solve(board.clone()).unwrap();
+        assert_eq!((cheapest.len(), total), (3, 6));
+        assert_eq!(cost(&cheapest), total);
+        assert!(board.replay(&cheapest).unwrap().is_won);
+    }
+
+    #[test]
+    fn solve_path_replays_to_the_solved_board() {
+        for board in [Board::sample(), crate::generate_seeded(6, 6, 8, 3)] {
+            let mut visited = Visited::default();
+            let (end, steps) = solve(board.clone(), &mut visited).unwrap();
+            let path = solve_path(board.clone()).unwrap();
+            assert_eq!(path.len() as u32, steps);
+            let replayed = board.replay(&path).unwrap();
+            assert!(replayed.is_won);
+            assert_eq!(replayed, end);
+        }
+        assert_eq!(solve_path(stuck_board()), None);
+    }
+
+    #[test]
+    fn hints_lie_on_an_optimal_path() {
+        let board = Board::sample();
+        let moves = solve_path(board.clone()).unwrap().len();
+        let mov = match board.hint() {
+            Hint::Play(mov) => mov,
+            hint => panic!("expected a move, got {:?}", hint),
+        };
+        assert!(board.is_legal_move(&mov));
+        assert_eq!(solve_path(board.play(&mov)).unwrap().len(), moves - 1);
+
+        let won = board.replay(&solve_path(board.clone()).unwrap()).unwrap();
+        assert_eq!(won.hint(), Hint::Won);
+        assert_eq!(stuck_board().hint(), Hint::Unsolvable);
+    }
+
+    #[test]
+    fn progress_is_reported_every_wave() {
+        let mut reports = vec![];
+        let solution = solve_with_progress(Board::sample(), |stats| {
+            reports.push((stats.depth, stats.states_explored));
+        });
+        assert_eq!(solution.map(|s| s.len()), Some(50));
+        assert!(!reports.is_empty());
+        // the search only goes deeper and finds more boards
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(reports.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert!(reports.iter().all(|&(depth, _)| depth <= 50));
+    }
+
+    #[test]
+    fn iddfs_matches_bfs() {
+        for board in [Board::sample(), crate::generate_seeded(5, 5, 6, 1)] {
+            let optimal = solve_path(board.clone()).map(|s| s.len());
+            let solution = iddfs_solve(board.clone());
+            assert_eq!(solution.as_ref().map(Vec::len), optimal);
+            if let Some(moves) = solution {
+                assert!(board.replay(&moves).unwrap().is_won);
+            }
+        }
+        assert_eq!(iddfs_solve(stuck_board()), None);
+    }
+
+    #[test]
+    fn frozen_piece_in_the_way() {
+        let frozen = |frozen| {
+            let pieces = vec![
+                Piece::new((3, 0), 2, Direction::Vertical).with_frozen(frozen),
+                Piece::marked((0, 1), 2, Direction::Horizontal),
+            ];
+            Board::new(6, 4, (5, 1), pieces)
+        };
+
+        // the piece can get out of the way unless it is frozen
+        assert!(solve_path(frozen(false)).is_some());
+        let board = frozen(true);
+        assert!(board.pieces()[0].is_frozen());
+        assert!(board.all_moves().iter().all(|mov| mov.get_piece() != 0));
+        // it still blocks the others
+        assert!(board.is_legal_move(&Move::Right(1, 1)));
+        assert!(!board.is_legal_move(&Move::Right(1, 2)));
+        assert_eq!(solve_path(board.clone()), None);
+        assert_eq!(Piece::frozen((3, 0), 2, Direction::Vertical), board.pieces()[0]);
+    }
+
+    #[test]
+    fn cached_solves_match_uncached_ones() {
+        let mut cache = MoveCache::new();
+        assert!(cache.is_empty());
+        let board = Board::sample();
+        let uncached = solve_path(board.clone());
+        assert_eq!(solve_with_cache(board.clone(), &mut cache), uncached);
+        let boards = cache.len();
+        assert!(boards > 0);
+
+        // the moves of every board are in the cache the second time
+        let hits = cache.hits();
+        assert_eq!(solve_with_cache(board.clone(), &mut cache), uncached);
+        assert_eq!(cache.len(), boards);
+        assert!(cache.hits() > hits);
+        assert_eq!(cache.moves(&board), board.all_moves());
+
+        cache.clear();
+        assert!(cache.is_empty() && cache.hits() == 0);
+        assert_eq!(solve_with_cache(stuck_board(), &mut cache), None);
+    }
+
+    #[test]
+    fn slides_and_cells_of_the_sample_solution() {
+        let (solution, stats) = solve_with_stats(Board::sample());
+        let solution = solution.unwrap();
+        assert_eq!(stats.slide_count, 50);
+        assert_eq!(stats.slide_count as usize, solution.len());
+        assert_eq!(stats.cell_count, solution.iter().map(Move::steps).sum::<u32>());
+        // some of the moves slide further than a tile
+        assert!(stats.cell_count > stats.slide_count);
+
+        let (solution, stats) = solve_with_stats(stuck_board());
+        assert_eq!(solution, None);
+        assert_eq!((stats.slide_count, stats.cell_count), (0, 0));
+    }
+
+    #[test]
+    fn fewest_pieces_rather_than_moves() {
+        // the tile can go round `a` by itself, or wait for `a` to move
+        let board = Board::from_ascii("X.a.\n..a.\n....", (3, 0)).unwrap();
+        let pickups = |moves: &[Move]| {
+            let pieces: Vec<usize> = moves.iter().map(Move::get_piece).collect();
+            pieces.windows(2).filter(|pair| pair[0] != pair[1]).count() + 1
+        };
+
+        let fewest_moves = solve_path(board.clone()).unwrap();
+        assert_eq!((fewest_moves.len(), pickups(&fewest_moves)), (2, 2));
+        let fewest_pieces = solve_min_pieces(board.clone()).unwrap();
+        assert_eq!(pickups(&fewest_pieces), 1);
+        assert!(fewest_pieces.len() >= 3);
+        assert!(board.replay(&fewest_pieces).unwrap().is_won);
+
+        assert_eq!(solve_min_pieces(stuck_board()), None);
+    }
+
+    #[test]
+    fn solve_with_limited_moves() {
+        let board = Board::sample();
+        let (_, single_steps) = solve_single_steps(board.clone(), &mut Visited::default()).unwrap();
+        let one = solve_limited(board.clone(), 1).unwrap();
+        assert_eq!(one.len() as u32, single_steps);
+        assert!(one.iter().all(|mov| mov.steps() == 1));
+
+        let two = solve_limited(board.clone(), 2).unwrap();
+        assert!(two.iter().all(|mov| mov.steps() <= 2));
+        assert!(board.replay(&two).unwrap().is_won);
+        assert!(two.len() <= one.len() && two.len() >= 50);
+        assert_eq!(solve_limited(board, u32::MAX).map(|s| s.len()), Some(50));
+    }
+
+    #[test]
+    fn bitboard_solution_replays() {
+        let board = Board::sample();
+        let solution = solve_bitboard(board.to_bitboard().unwrap()).unwrap();
+        assert_eq!(solution.len(), 50);
+        assert!(board.replay(&solution).unwrap().is_won);
+    }
+
+    #[test]
+    fn timeouts() {
+        let board = Board::sample();
+        let result = solve_with_timeout(board.clone(), Duration::ZERO);
+        assert!(matches!(result, Err(SolveError::Timeout { .. })));
+
+        let solved = solve(board.clone(), &mut Visited::default()).unwrap();
+        assert_eq!(solved.1, 50);
+        assert_eq!(solve_with_timeout(board, Duration::from_secs(600)), Ok(solved));
+    }
+
+    #[test]
+    fn state_limits() {
+        let board = Board::sample();
+        // the table is first checked once the boards a move away are in it
+        let first_wave = 1 + board.all_moves().len();
+        let result = solve_with_state_limit(board.clone(), 1);
+        assert_eq!(result, Err(SolveError::StateLimitExceeded { states_explored: first_wave }));
+
+        // the table stops growing a wave after it reaches the limit,
+        // long before every one of the 4643 reachable boards is visited
+        match solve_with_state_limit(board.clone(), 100) {
+            Err(SolveError::StateLimitExceeded { states_explored }) => {
+                assert!((100..1000).contains(&states_explored), "{}", states_explored);
+            }
+            result => panic!("expected the limit to be reached, got {:?}", result),
+        }
+
+        let solved = solve(board.clone(), &mut Visited::default()).unwrap();
+        assert_eq!(solved.1, 50);
+        assert_eq!(solve_with_state_limit(board, 100_000), Ok(solved));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::board::*;
+use std::fmt::Write;
+
+/// The width and height of a tile in pixels
+const CELL: u32 = 50;
+/// The space between a piece and the edges of its tiles
+const GAP: u32 = 3;
+/// The number of seconds each move takes
+const SECONDS_PER_MOVE: f64 = 0.5;
+const MARKED_COLOR: &str = "#d62728";
+const WALL_COLOR: &str = "#444444";
+const PIECE_COLORS: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#9467bd", "#8c564b", "#e377c2", "#bcbd22", "#17becf",
+];
+
+/// Draw a solution as an SVG that animates the pieces sliding through
+/// every move, the marked piece is red and the goal is marked with an arrow.
+/// The moves are assumed to be legal, like in `Board::play`.
+pub fn solution_to_svg(start: &Board, moves: &[Move]) -> String {
+    // every board along the solution, starting with the start
+    let mut boards = vec![start.clone()];
+    for mov in moves {
+        let next = boards[boards.len() - 1].play(mov);
+        boards.push(next);
+    }
+
+    let (width, height) = (start.width * CELL, start.height * CELL);
+    let mut svg = String::new();
+    // writing to a string can't fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    );
+    let _ = writeln!(svg, r##"<rect width="{}" height="{}" fill="#eeeeee"/>"##, width, height);
+
+    for &(x, y) in &start.walls {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x * CELL,
+            y * CELL,
+            CELL,
+            CELL,
+            WALL_COLOR
+        );
+    }
+
+    svg.push_str(&goal_arrow(start));
+
+    let duration = moves.len() as f64 * SECONDS_PER_MOVE;
+    for (i, piece) in start.pieces().iter().enumerate() {
+        let (w, h) = match piece.direction() {
+            Direction::Horizontal => (piece.size() * CELL, CELL),
+            Direction::Vertical => (CELL, piece.size() * CELL),
+        };
+        let color = if piece.is_marked() {
+            MARKED_COLOR
+        } else {
+            PIECE_COLORS[i % PIECE_COLORS.len()]
+        };
+        let (x, y) = piece.location();
+
+        let _ = write!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="8" fill="{}">"#,
+            x * CELL + GAP,
+            y * CELL + GAP,
+            w - 2 * GAP,
+            h - 2 * GAP,
+            color
+        );
+
+        if !moves.is_empty() {
+            let locations: Vec<Tile> = boards.iter().map(|b| b.pieces()[i].location()).collect();
+            svg.push_str(&animation("x", locations.iter().map(|l| l.0), duration));
+            svg.push_str(&animation("y", locations.iter().map(|l| l.1), duration));
+        }
+        svg.push_str("</rect>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// An animation of a coordinate of a piece through the given tile positions,
+/// spending the same amount of time on each move
+fn animation(attribute: &str, positions: impl Iterator<Item = u32>, duration: f64) -> String {
+    let values: Vec<String> = positions.map(|p| (p * CELL + GAP).to_string()).collect();
+    let steps = values.len() - 1;
+    let key_times: Vec<String> = (0..=steps)
+        .map(|i| format!("{:.4}", i as f64 / steps as f64))
+        .collect();
+
+    format!(
+        r#"<animate attributeName="{}" values="{}" keyTimes="{}" dur="{}s" fill="freeze"/>"#,
+        attribute,
+        values.join(";"),
+        key_times.join(";"),
+        duration
+    )
+}
+
+/// A triangle on the goal tile pointing the way the marked piece has to go
+fn goal_arrow(board: &Board) -> String {
+    let (gx, gy) = board.goal;
+    let (left, top) = (gx * CELL, gy * CELL);
+    let (right, bottom, mid_x, mid_y) = (left + CELL, top + CELL, left + CELL / 2, top + CELL / 2);
+    let inset = CELL / 4;
+
+    let marked = board.pieces().iter().find(|p| p.is_marked());
+    let points = match marked.map(|p| (p.direction(), p.location())) {
+        Some((Direction::Horizontal, (x, _))) if x > gx => [
+            (right - inset, top + inset),
+            (right - inset, bottom - inset),
+            (left + inset, mid_y),
+        ],
+        Some((Direction::Vertical, (_, y))) if y > gy => [
+            (left + inset, bottom - inset),
+            (right - inset, bottom - inset),
+            (mid_x, top + inset),
+        ],
+        Some((Direction::Vertical, _)) => [
+            (left + inset, top + inset),
+            (right - inset, top + inset),
+            (mid_x, bottom - inset),
+        ],
+        _ => [
+            (left + inset, top + inset),
+            (left + inset, bottom - inset),
+            (right - inset, mid_y),
+        ],
+    };
+
+    let points: Vec<String> = points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+    format!(
+        r#"<polygon points="{}" fill="{}" opacity="0.5"/>
+"#,
+        points.join(" "),
+        MARKED_COLOR
+    )
+}
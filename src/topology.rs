@@ -0,0 +1,156 @@
+use crate::board::*;
+use core::any::Any;
+use core::fmt;
+
+/// How the tiles of a board are connected, which decides where the pieces
+/// can go. Boards are rectangular unless they are given another topology
+/// with `Board::with_topology`. The pieces are still checked to be on the
+/// board as a rectangle when the board is created.
+pub trait Topology: Any + fmt::Debug + Send + Sync {
+    /// Get the tile `steps` tiles away from the tile towards the side of a
+    /// board of the given size, or `None` if there is no such tile
+    fn step(&self, tile: Tile, towards: Side, steps: u32, width: u32, height: u32) -> Option<Tile>;
+
+    /// Check if the tile is on a board of the given size
+    fn contains(&self, (x, y): Tile, width: u32, height: u32) -> bool {
+        x < width && y < height
+    }
+
+    /// Check if the rows and columns end at the edges of the board, which
+    /// the quick checks and estimates of the solvers rely on. They are
+    /// left out when this is false, so it is the safe default.
+    fn is_rectangular(&self) -> bool {
+        false
+    }
+}
+
+/// The topology of an ordinary board, where the pieces stop at the edges
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct RectTopology;
+
+impl Topology for RectTopology {
+    fn step(
+        &self,
+        (x, y): Tile,
+        towards: Side,
+        steps: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<Tile> {
+        let tile = match towards {
+            Side::Left => (x.checked_sub(steps)?, y),
+            Side::Right => (x.checked_add(steps)?, y),
+            Side::Top => (x, y.checked_sub(steps)?),
+            Side::Bottom => (x, y.checked_add(steps)?),
+        };
+        Some(tile).filter(|&tile| self.contains(tile, width, height))
+    }
+
+    fn is_rectangular(&self) -> bool {
+        true
+    }
+}
+
+/// The topology of a board where every edge is joined to the opposite one,
+/// so a piece that slides off the right edge comes back on the left edge
+/// and one that slides off the bottom comes back at the top
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct ToroidalTopology;
+
+impl Topology for ToroidalTopology {
+    fn step(
+        &self,
+        (x, y): Tile,
+        towards: Side,
+        steps: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<Tile> {
+        if !self.contains((x, y), width, height) {
+            return None;
+        }
+        // going back by `steps` is going forward by the rest of the way around
+        let forward = |i: u32, steps: u32, length: u32| {
+            ((u64::from(i) + u64::from(steps)) % u64::from(length)) as u32
+        };
+        let back = |i, length| forward(i, length - steps % length, length);
+        Some(match towards {
+            Side::Left => (back(x, width), y),
+            Side::Right => (forward(x, steps, width), y),
+            Side::Top => (x, back(y, height)),
+            Side::Bottom => (x, forward(y, steps, height)),
+        })
+    }
+}
+
+/// The topology of a board. Topologies are compared by their type, so
+/// they shouldn't have fields that change how they behave.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BoardTopology(pub(crate) &'static dyn Topology);
+
+impl PartialEq for BoardTopology {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b): (&dyn Any, &dyn Any) = (self.0, other.0);
+        a.type_id() == b.type_id()
+    }
+}
+
+impl Eq for BoardTopology {}
+
+impl Default for BoardTopology {
+    fn default() -> Self {
+        BoardTopology(&RectTopology)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn toroidal_steps_wrap_around() {
+        let step = |tile, towards, steps| ToroidalTopology.step(tile, towards, steps, 5, 3);
+        assert_eq!(step((4, 0), Side::Right, 1), Some((0, 0)));
+        assert_eq!(step((0, 0), Side::Left, 1), Some((4, 0)));
+        assert_eq!(step((1, 0), Side::Top, 1), Some((1, 2)));
+        assert_eq!(step((1, 2), Side::Bottom, 4), Some((1, 0)));
+        assert_eq!(step((1, 1), Side::Right, 5), Some((1, 1)));
+        assert_eq!(step((5, 0), Side::Right, 1), None);
+
+        assert_eq!(RectTopology.step((4, 0), Side::Right, 1, 5, 3), None);
+        assert_eq!(RectTopology.step((0, 0), Side::Left, 1, 5, 3), None);
+        assert_eq!(RectTopology.step((1, 0), Side::Right, 3, 5, 3), Some((4, 0)));
+    }
+
+    #[test]
+    fn pieces_wrap_from_the_right_edge_to_the_left() {
+        let pieces = vec![
+            Piece::marked((3, 0), 2, Direction::Horizontal),
+            Piece::new((1, 1), 2, Direction::Vertical),
+        ];
+        let board = Board::new(5, 3, (0, 0), pieces).with_topology(&ToroidalTopology);
+        assert!(!board.topology().is_rectangular());
+        let mut moves = board.all_moves();
+        moves.sort();
+        let mut expected: Vec<Move> = (1..=3)
+            .flat_map(|steps| [Move::Left(0, steps), Move::Right(0, steps)])
+            .collect();
+        expected.extend([Move::Up(1, 1), Move::Down(1, 1)]);
+        expected.sort();
+        assert_eq!(moves, expected);
+
+        let wrapped = board.play(&Move::Right(0, 1));
+        let tiles: Vec<Tile> = wrapped.piece_tiles(&wrapped.pieces()[0]).collect();
+        assert_eq!(tiles, [(4, 0), (0, 0)]);
+        assert!(wrapped.is_won);
+        assert_eq!(wrapped.to_string().lines().next(), Some("X...X"));
+        assert_eq!(wrapped.play(&Move::Left(0, 1)), board);
+
+        // the same move runs into the edge of a rectangular board
+        let rectangular = board.with_topology(&RectTopology);
+        assert!(!rectangular.is_legal_move(&Move::Right(0, 1)));
+    }
+}
@@ -0,0 +1,18 @@
+use crate::board::*;
+use crate::solver::solve_path;
+use wasm_bindgen::prelude::*;
+
+/// Parse an ASCII board, solve it and return the moves of the solution
+/// as a JSON array. Parse errors and unsolvable boards are thrown as
+/// exceptions on the JavaScript side.
+#[wasm_bindgen]
+pub fn solve_ascii(grid: &str, goal_x: u32, goal_y: u32) -> Result<JsValue, JsValue> {
+    let board =
+        Board::from_ascii(grid, (goal_x, goal_y)).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let moves =
+        solve_path(board).ok_or_else(|| JsValue::from_str("the board has no solution"))?;
+    serde_json::to_string(&moves)
+        .map(|json| JsValue::from_str(&json))
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
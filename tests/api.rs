@@ -0,0 +1,19 @@
+//! The library used from another crate, the way the binary uses it
+use blocked::*;
+
+#[test]
+fn solve_the_sample_board() {
+    let board = Board::sample();
+    let moves = solve_path(board.clone()).expect("the sample board can be solved");
+    assert_eq!(moves.len(), 50);
+    assert!(board.replay(&moves).unwrap().is_won);
+}
+
+#[test]
+fn parse_and_solve() {
+    let board = Board::from_ascii("a...\na..b\nXX.b", (3, 2)).unwrap();
+    let (solution, stats) = solve_with_stats(board.clone());
+    let solution = solution.unwrap();
+    assert_eq!(stats.depth as usize, solution.len());
+    assert!(board.replay(&solution).unwrap().is_won);
+}
@@ -0,0 +1,19 @@
+//! The boards and moves build without the `std` feature
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn core_compiles_without_std() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // a target directory of its own, so that the check doesn't wait for
+    // the lock of the build that runs the tests or throw away its output
+    let status = Command::new(env!("CARGO"))
+        .args(["check", "--lib", "--no-default-features", "--quiet"])
+        .arg("--manifest-path")
+        .arg(manifest.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(manifest.join("target").join("no_std"))
+        .status()
+        .expect("cargo can be run");
+    assert!(status.success(), "the crate doesn't build without std");
+}